@@ -0,0 +1,18 @@
+use gat_std::collections::BitVec;
+use gat_std::gatify;
+
+#[gatify]
+fn main() {
+    let mut bits = BitVec::from_elem(false, 16);
+
+    bits[3] = true;
+    *&mut bits[5] = true;
+    (&mut bits[3]).toggle();
+
+    println!("{} {}", &bits[3], &bits[5]);
+
+    for mut bit in bits.iter_mut() {
+        bit.toggle();
+    }
+    println!("{:?}", bits);
+}
@@ -0,0 +1,59 @@
+use gat_std::collections::{DequeRange, DequeRangeMut};
+use gat_std::gatify;
+use gat_std::ops::{Deref, DerefMut};
+
+/// A queue stored as two separate halves, which derefs to a view of both
+struct Split {
+    front: Vec<u32>,
+    back: Vec<u32>,
+}
+
+impl Deref for Split {
+    type Target<'a> = DequeRange<'a, u32>;
+
+    fn deref(&self) -> Self::Target<'_> {
+        DequeRange::from_slices(&self.front, &self.back)
+    }
+}
+
+impl DerefMut for Split {
+    type TargetMut<'a> = DequeRangeMut<'a, u32>;
+
+    fn deref_mut(&mut self) -> Self::TargetMut<'_> {
+        DequeRangeMut::from_slices(&mut self.front, &mut self.back)
+    }
+}
+
+#[gatify]
+fn main() {
+    let mut split = Split {
+        front: vec![1, 2],
+        back: vec![3],
+    };
+
+    for val in &mut *split {
+        *val *= 2;
+    }
+
+    let view = &*split;
+    println!("{:?} has {} items", view, view.len());
+
+    // In value position, `*` yields the proxy target
+    println!("{:?}", *split);
+
+    // Core dereferences are unaffected
+    let boxed = Box::new(5);
+    let num = &mut 3;
+    *num += *boxed;
+    (*num).clone_from(&8);
+    println!("{}", *num);
+
+    // Places such as `let` initializers and `match` scrutinees are left to the core operator, so
+    // `*` can move out of a box or be matched by reference
+    let mut name = Box::new(Some(String::from("split")));
+    if let Some(ref mut name) = *name {
+        name.push('!');
+    }
+    let name = *name;
+    println!("{:?}", name);
+}
@@ -0,0 +1,83 @@
+use std::marker::PhantomData;
+use std::ops::RangeFrom;
+
+use gat_std::collections::BitVec;
+use gat_std::iter::{DoubleEndedIterator, IntoLending, Iterator};
+use gat_std::ops::{Index, IndexMut};
+
+struct Countdown(u32);
+
+impl Iterator for Countdown {
+    type Item<'a> = &'a mut u32
+    where
+        Self: 'a;
+
+    fn next(&mut self) -> Option<Self::Item<'_>> {
+        if self.0 > 0 {
+            self.0 -= 1;
+            Some(&mut self.0)
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0 as usize, Some(self.0 as usize))
+    }
+}
+
+#[derive(gat_std::Iterator)]
+struct Wrapper(Countdown);
+
+#[derive(gat_std::Iterator, gat_std::DoubleEndedIterator)]
+struct Tagged<I, T>
+where
+    I: Iterator,
+{
+    #[gat_std(forward)]
+    inner: I,
+    _tag: PhantomData<T>,
+}
+
+#[derive(gat_std::Index, gat_std::IndexMut)]
+struct Flags(BitVec);
+
+mod facade {
+    pub use gat_std as gat;
+}
+
+#[derive(gat_std::Index)]
+#[gat_std(crate = crate::facade::gat, index = usize, index = RangeFrom<usize>)]
+struct Numbers<'a> {
+    #[gat_std(forward)]
+    values: Vec<u32>,
+    name: &'a str,
+}
+
+fn main() {
+    let mut wrapper = Wrapper(Countdown(3));
+    assert_eq!(wrapper.size_hint(), (3, Some(3)));
+    assert_eq!(wrapper.next().copied(), Some(2));
+    assert_eq!(wrapper.nth(1).copied(), Some(0));
+    assert!(wrapper.next().is_none());
+
+    let slice = [1, 2, 3];
+    let mut tagged = Tagged::<_, ()> {
+        inner: slice.iter().into_lending(),
+        _tag: PhantomData,
+    };
+    assert_eq!(tagged.next_back(), Some(&3));
+    assert_eq!(tagged.next(), Some(&1));
+
+    let mut flags = Flags(BitVec::from_elem(false, 4));
+    *flags.index_mut(1) = true;
+    assert!(flags.index(1) && !flags.index(0));
+
+    let numbers = Numbers {
+        values: slice.to_vec(),
+        name: "numbers",
+    };
+    assert_eq!(*numbers.index(2), 3);
+    assert_eq!(numbers.index(1..), [2, 3]);
+    println!("{}", numbers.name);
+}
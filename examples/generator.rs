@@ -0,0 +1,106 @@
+use gat_std::iter::Iterator;
+use gat_std::lending_iter;
+
+/// Lends each row of a grid, skipping rows of zeroes and stopping at the first negative cell
+fn rows(width: usize, cells: Vec<i32>) -> impl for<'a> Iterator<Item<'a> = &'a mut [i32]> {
+    lending_iter! {
+        move(width: usize, mut cells: Vec<i32>) -> &mut [i32] {
+            let mut row: usize = 0;
+            'rows: loop {
+                let start: usize = row * width;
+                if start >= cells.len() {
+                    break;
+                }
+                row += 1;
+                for cell in &cells[start..start + width] {
+                    if *cell < 0 {
+                        return;
+                    }
+                }
+                if cells[start..start + width].iter().all(|cell| *cell == 0) {
+                    continue 'rows;
+                }
+                yield &mut cells[start..start + width];
+            }
+        }
+    }
+}
+
+struct Pair {
+    index: usize,
+    total: u32,
+}
+
+/// Lends a running total, split at each multiple of `every`, until it passes `limit`
+fn totals(every: u32, limit: u32) -> impl for<'a> Iterator<Item<'a> = &'a mut Pair> {
+    lending_iter! {
+        move(every: u32, limit: u32, pair: Pair = Pair { index: 0, total: 0 }) -> &mut Pair {
+            let mut n: u32 = 0;
+            'outer: loop {
+                n += 1;
+                for _ in 0..n {
+                    if pair.total > limit {
+                        break 'outer;
+                    }
+                }
+                if n % every == 0 {
+                    let index: usize = pair.index + 1;
+                    pair = Pair { index, total: 0 };
+                    yield &mut pair;
+                } else {
+                    pair.total += n;
+                    yield &mut pair;
+                }
+            }
+            assert!(pair.total > limit, "stopped early at {}", pair.total);
+        }
+    }
+}
+
+fn main() {
+    // Fills a buffer with each line of some borrowed text in turn, lending it out
+    let text = String::from("one\ntwo\nthree");
+    let mut iter = lending_iter! {
+        move(text: &str = &text, buf: String = String::new()) -> &mut String {
+            let mut rest: &str = text;
+            while !rest.is_empty() {
+                let end: usize = rest.find('\n').unwrap_or(rest.len());
+                buf.clear();
+                buf.push_str(&rest[..end]);
+                rest = rest.get(end + 1..).unwrap_or("");
+                yield &mut buf;
+            }
+        }
+    };
+
+    let mut found = Vec::new();
+    while let Some(line) = iter.next() {
+        line.push('!');
+        found.push(line.clone());
+    }
+    assert_eq!(found, ["one!", "two!", "three!"]);
+    assert!(iter.next().is_none());
+
+    let mut sum = 0;
+    rows(2, vec![1, 2, 0, 0, 3, 4, -1, 5, 6, 7]).for_each(|row| {
+        row[0] *= 10;
+        sum += row[0] + row[1];
+    });
+    assert_eq!(sum, 46);
+
+    let mut seen = Vec::new();
+    totals(3, 10).for_each(|pair| seen.push((pair.index, pair.total)));
+    assert_eq!(
+        seen,
+        [
+            (0, 1),
+            (0, 3),
+            (1, 0),
+            (1, 4),
+            (1, 9),
+            (2, 0),
+            (2, 7),
+            (2, 15)
+        ]
+    );
+}
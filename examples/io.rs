@@ -0,0 +1,31 @@
+use gat_std::gatify;
+use gat_std::io::BufReadExt;
+use gat_std::iter::{Iterator, TryIterator};
+
+#[gatify]
+fn main() {
+    let log = "INFO start\nWARN disk low\nINFO tick\nERROR disk full\n";
+
+    for line in log.as_bytes().lines_reusing() {
+        let line = line.unwrap();
+        if !line.starts_with("INFO") {
+            println!("{}", line);
+        }
+    }
+
+    let warnings = log
+        .as_bytes()
+        .lines_reusing()
+        .touch(|line| line.make_ascii_lowercase())
+        .filter(|line| line.contains("disk"))
+        .fold(0, |acc, _| acc + 1)
+        .unwrap();
+    println!("{} disk messages", warnings);
+
+    let total = log
+        .as_bytes()
+        .split_reusing(b' ')
+        .into_results()
+        .fold(0, |acc, word| acc + word.unwrap().len());
+    println!("{} bytes of words", total);
+}
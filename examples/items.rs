@@ -0,0 +1,123 @@
+use gat_std::collections::BitVec;
+use gat_std::iter::Iterator;
+use gat_std::{gatify, gatify_block};
+
+struct Countdown(u32);
+
+impl Iterator for Countdown {
+    type Item<'a> = &'a mut u32
+    where
+        Self: 'a;
+
+    fn next(&mut self) -> Option<Self::Item<'_>> {
+        if self.0 > 0 {
+            self.0 -= 1;
+            Some(&mut self.0)
+        } else {
+            None
+        }
+    }
+}
+
+#[gatify]
+mod module {
+    use super::*;
+
+    pub fn count(flags: &Flags) -> usize {
+        let mut out = 0;
+        for i in 0..flags.0.len() {
+            if flags.0[i] {
+                out += 1;
+            }
+        }
+        out
+    }
+}
+
+struct Flags(BitVec);
+
+#[gatify]
+impl Flags {
+    const LEN: usize = [4, 8][0];
+
+    fn set(&mut self, idx: usize) {
+        self.0[idx] = true;
+    }
+
+    const fn len() -> usize {
+        [Self::LEN][0]
+    }
+}
+
+impl Flags {
+    #[gatify]
+    fn get(&self, idx: usize) -> bool {
+        self.0[idx]
+    }
+
+    // An inner `#[gatify]` is expanded with its own arguments
+    #[gatify(for_loops)]
+    fn sum(&self) -> u32 {
+        let mut out = 0;
+        for val in Countdown(4) {
+            out += *val;
+        }
+        out
+    }
+}
+
+trait Values {
+    fn values(&self) -> &[u32];
+
+    #[gatify]
+    fn head(&self) -> u32 {
+        self.values()[0]
+    }
+}
+
+impl Values for Vec<u32> {
+    fn values(&self) -> &[u32] {
+        self
+    }
+}
+
+#[gatify]
+const FIRST: fn(&Flags) -> bool = |flags| flags.0[0];
+
+#[gatify]
+enum Size {
+    Small = [1, 2][0],
+}
+
+gatify_block! {
+    fn total() -> u32 {
+        let mut out = 0;
+        for val in Countdown(3) {
+            out += *val;
+        }
+        out
+    }
+}
+
+fn main() {
+    let mut flags = Flags(BitVec::from_elem(false, Flags::len()));
+    flags.set(1);
+    flags.set(2);
+    println!("{} {} {}", flags.get(1), FIRST(&flags), module::count(&flags));
+    println!("{} {} {}", vec![3, 4].head(), flags.sum(), total());
+    println!("{}", Size::Small as i32);
+
+    // Gatified regions inside non-gatified code
+    let last = gatify_block! { flags.0[3] };
+    gatify_block! {
+        #![gatify(index)]
+        let second = flags.0[1];
+        let check = |idx: usize| flags.0[idx];
+    }
+    let both = gatify_block! {{
+        let third = flags.0[2];
+        second && third
+    }};
+    println!("{} {} {} {}", last, second, both, check(2));
+    println!("{} bits", Flags::LEN);
+}
@@ -0,0 +1,93 @@
+use gat_std::iter::{DoubleEndedIterator, Iterator};
+use gat_std::lending_iterator;
+
+/// Overlapping mutable windows of a slice
+struct WindowsMut<'s, T> {
+    slice: &'s mut [T],
+    size: usize,
+    front: usize,
+    back: usize,
+}
+
+impl<'s, T> WindowsMut<'s, T> {
+    fn new(slice: &'s mut [T], size: usize) -> Self {
+        let back = slice.len().saturating_sub(size - 1);
+        WindowsMut {
+            slice,
+            size,
+            front: 0,
+            back,
+        }
+    }
+}
+
+#[lending_iterator]
+impl<'s, T> WindowsMut<'s, T> {
+    pub fn next(&mut self) -> Option<&mut [T]> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        let start = self.front - 1;
+        Some(&mut self.slice[start..start + self.size])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+
+    fn next_back<'w>(&'w mut self) -> Option<&'w mut [T]> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(&mut self.slice[self.back..self.back + self.size])
+    }
+
+    fn remaining(&self) -> usize {
+        self.back - self.front
+    }
+}
+
+/// Lends a buffer alongside a borrowed label
+struct Labelled<'a> {
+    label: &'a str,
+    buf: String,
+    count: usize,
+}
+
+#[lending_iterator(crate = ::gat_std)]
+impl<'a> Labelled<'a> {
+    fn next(&mut self) -> Option<(&'a str, &mut String)> {
+        if self.count == 0 {
+            return None;
+        }
+        self.count -= 1;
+        self.buf.push('!');
+        Some((self.label, &mut self.buf))
+    }
+}
+
+fn main() {
+    let mut nums = [1, 2, 3, 4];
+    let mut windows = WindowsMut::new(&mut nums, 2);
+    assert_eq!(windows.size_hint(), (3, Some(3)));
+    if let Some(last) = windows.next_back() {
+        last[1] += 10;
+    }
+    while let Some(window) = windows.next() {
+        window[1] += window[0];
+    }
+    assert_eq!(windows.remaining(), 0);
+    assert_eq!(nums, [1, 3, 6, 14]);
+
+    let mut labelled = Labelled {
+        label: "hi",
+        buf: String::new(),
+        count: 2,
+    };
+    let first = labelled.next().map(|(label, buf)| format!("{label}{buf}"));
+    assert_eq!(first.as_deref(), Some("hi!"));
+    assert_eq!(labelled.nth(0).map(|(_, buf)| buf.len()), Some(2));
+}
@@ -1,5 +1,14 @@
+use gat_std::collections::BitVec;
 use gat_std::gatify;
 
+struct Row {
+    cells: Vec<u32>,
+}
+
+struct Grid {
+    rows: Vec<Row>,
+}
+
 #[gatify]
 fn main() {
     for a in 0..10 {
@@ -14,4 +23,95 @@ fn main() {
     let first = &mut b[0];
     *first = 1;
     println!("{:?}", b);
+
+    // Bare indexing dereferences core outputs, and passes GAT outputs through
+    let sum = b[0] + b[1];
+    let words = vec![String::from("a"), String::from("bc")];
+    let bits = BitVec::from_elem(true, 3);
+    println!("{} {} {}", sum, words[1].len(), bits[2]);
+    let slice = &words[..];
+    println!("{}", slice[0]);
+
+    // Bare indexing looks through references, and method receivers use `IndexMut` when needed
+    let (bits_ref, words_ref) = (&bits, &&words);
+    println!("{} {}", bits_ref[0], words_ref[0]);
+    let mut nested = vec![vec![1]];
+    let nested_mut = &mut nested;
+    nested_mut[0].push(2);
+    println!("{:?}", nested);
+
+    // Nested indexing, through fields and into proxy values
+    let mut matrix = vec![vec![1, 2], vec![3, 4]];
+    matrix[1][0] += 10;
+    let corner = &mut matrix[0][1];
+    *corner = 0;
+    println!("{:?} {}", &matrix[1], matrix[1][0] + matrix[0][1]);
+
+    let mut grid = Grid {
+        rows: vec![Row { cells: vec![5; 3] }],
+    };
+    *&mut grid.rows[0].cells[2] = 7;
+    grid.rows[0].cells[1] = 6;
+    let cells = &mut grid.rows[0].cells;
+    cells.push(8);
+    println!("{:?}", &grid.rows[0].cells[..]);
+
+    let mut planes = vec![BitVec::from_elem(false, 4); 2];
+    planes[1][3] = true;
+    {
+        let mut bit = &mut planes[0][2];
+        *bit = true;
+    }
+    println!("{} {} {}", planes[0][2], planes[1][3], &planes[1][0]);
+
+    // Indexing inside known macros
+    let repeated = vec![b[2]; b[0]];
+    assert_eq!(repeated, [2], "{:?}", &b[..]);
+    assert!(matches!(b[1], 1..=2 if b[2] == 2));
+    let text = format!("{}-{width}", words[1], width = b[2]);
+    debug_assert!(text.len() == words[1].len() + 2);
+    core::assert!(b[2] == 2);
+    // Unknown macros are left untouched, as are user macros sharing a name with a known one
+    println!("{}", stringify!(b[0]));
+    facade::assert!(b[0]);
+}
+
+mod facade {
+    pub use gat_std as gat;
+
+    macro_rules! assert {
+        ($val:expr) => {
+            ::core::assert_eq!(::core::stringify!($val), "b[0]")
+        };
+    }
+    pub(crate) use assert;
+}
+
+#[gatify(crate = crate::facade::gat, skip(deref))]
+fn _facade(bits: &mut BitVec, mut nums: Vec<u32>) {
+    bits[0] = true;
+    let num = &mut nums[0];
+    // Only indexing is rewritten, so this is the core dereference
+    *num += 1;
+    for _ in nums {}
+}
+
+#[gatify(index)]
+fn _skipped(mut nums: Vec<u32>) -> u32 {
+    nums[0] = 1;
+    #[gatify::skip]
+    let first = &nums[0];
+    #[gatify::skip]
+    fn inner(nums: &[u32]) -> &u32 {
+        &nums[1]
+    }
+    *first + *inner(&nums) + #[gatify::skip] nums[2]
+}
+
+#[gatify]
+fn _borrowed<'a>(words: &[&'a str]) -> usize {
+    // Borrowing an index of non-'static values isn't supported by the GAT traits
+    #[gatify::skip]
+    let first: &&'a str = &words[0];
+    first.len()
 }
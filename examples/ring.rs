@@ -0,0 +1,30 @@
+use gat_std::collections::RingBuffer;
+use gat_std::gatify;
+use std::collections::VecDeque;
+
+#[gatify]
+fn main() {
+    let mut buf = RingBuffer::<u32, 4>::new();
+    for i in 0..4 {
+        buf.push_back(i).unwrap();
+    }
+    buf.pop_front();
+    buf.push_back(4).unwrap();
+
+    let view = &buf[1..];
+    println!(
+        "{:?} has {} items, split as {:?}",
+        view,
+        view.len(),
+        view.as_slices()
+    );
+
+    for val in &mut buf[..2] {
+        *val *= 10;
+    }
+    println!("{:?}", buf);
+
+    let deque = VecDeque::from([1, 2, 3, 4]);
+    let view = &deque[1..3];
+    println!("{:?} and {:?}", view, deque[2..]);
+}
@@ -0,0 +1,307 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::spanned::Spanned;
+use syn::{Data, DeriveInput, Generics, Ident, Lifetime, Member, Path, Token, Type};
+
+use crate::options::combine;
+
+/// A lifetime for the GATs of an impl, which can't clash with those of its generics
+pub fn gat_lifetime(generics: &Generics) -> Lifetime {
+    let mut name = String::from("a");
+    while generics
+        .lifetimes()
+        .any(|param| param.lifetime.ident == name)
+    {
+        name.insert(0, '_');
+    }
+    Lifetime::new(&format!("'{}", name), Span::call_site())
+}
+
+/// An argument of a `#[gat_std(..)]` attribute
+enum Arg {
+    Crate(Box<Path>),
+    Index(Box<Type>),
+    Forward,
+}
+
+impl Parse for Arg {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(Token![crate]) {
+            input.parse::<Token![crate]>()?;
+            input.parse::<Token![=]>()?;
+            return Path::parse_mod_style(input).map(|path| Arg::Crate(Box::new(path)));
+        }
+
+        let name = input.parse::<Ident>()?;
+        if name == "forward" {
+            Ok(Arg::Forward)
+        } else if name == "index" {
+            input.parse::<Token![=]>()?;
+            input.parse::<Type>().map(|ty| Arg::Index(Box::new(ty)))
+        } else {
+            Err(syn::Error::new(
+                name.span(),
+                format!(
+                    "unknown gat_std argument `{}`, expected `forward`, `index` or `crate`",
+                    name
+                ),
+            ))
+        }
+    }
+}
+
+/// Parse the `#[gat_std(..)]` attributes in a list, collecting any errors
+fn parse_args(attrs: &[syn::Attribute], errors: &mut Vec<syn::Error>) -> Vec<(Span, Arg)> {
+    let mut out = Vec::new();
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("gat_std")) {
+        let args = attr.parse_args_with(|input: ParseStream| {
+            input.parse_terminated::<_, Token![,]>(|input: ParseStream| {
+                let span = input.span();
+                Ok((span, input.parse::<Arg>()?))
+            })
+        });
+        match args {
+            Ok(args) => out.extend(args),
+            Err(e) => errors.push(e),
+        }
+    }
+    out
+}
+
+/// A struct with the field its impls forward to
+struct Forward {
+    input: DeriveInput,
+    krate: Path,
+    indices: Vec<Type>,
+    member: Member,
+    ty: Type,
+}
+
+impl Forward {
+    fn new(input: DeriveInput, derive: &str) -> syn::Result<Forward> {
+        let mut errors = Vec::new();
+
+        let mut krate = None;
+        let mut indices = Vec::new();
+        for (span, arg) in parse_args(&input.attrs, &mut errors) {
+            match arg {
+                Arg::Crate(path) if krate.is_none() => krate = Some(*path),
+                Arg::Crate(path) => {
+                    errors.push(syn::Error::new_spanned(path, "duplicate `crate` argument"))
+                }
+                Arg::Index(ty) => indices.push(*ty),
+                Arg::Forward => errors.push(syn::Error::new(
+                    span,
+                    "`forward` can only be used on a field",
+                )),
+            }
+        }
+
+        let fields = match &input.data {
+            Data::Struct(data) => &data.fields,
+            _ => {
+                let msg = format!("`{}` can only be derived for structs", derive);
+                return Err(syn::Error::new_spanned(&input.ident, msg));
+            }
+        };
+
+        let mut marked = Vec::new();
+        for (idx, field) in fields.iter().enumerate() {
+            for (span, arg) in parse_args(&field.attrs, &mut errors) {
+                match arg {
+                    Arg::Forward => marked.push((span, idx)),
+                    _ => errors.push(syn::Error::new(
+                        span,
+                        "only `forward` can be used on a field",
+                    )),
+                }
+            }
+        }
+
+        let idx = match (marked.as_slice(), fields.len()) {
+            ([(_, idx)], _) => *idx,
+            ([], 1) => 0,
+            ([], _) => {
+                let msg = format!(
+                    "`{}` needs a field to forward to, mark one with `#[gat_std(forward)]`",
+                    derive,
+                );
+                errors.push(syn::Error::new_spanned(&input.ident, msg));
+                0
+            }
+            ([_, rest @ ..], _) => {
+                for (span, _) in rest {
+                    errors.push(syn::Error::new(
+                        *span,
+                        "only one field can be marked `forward`",
+                    ));
+                }
+                0
+            }
+        };
+
+        if let Some(e) = combine(errors) {
+            return Err(e);
+        }
+
+        let field = &fields.iter().collect::<Vec<_>>()[idx];
+        let member = match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(syn::Index {
+                index: idx as u32,
+                span: field.ty.span(),
+            }),
+        };
+
+        if indices.is_empty() {
+            indices.push(syn::parse_quote!(usize));
+        }
+
+        Ok(Forward {
+            krate: krate.unwrap_or_else(|| syn::parse_quote!(::gat_std)),
+            indices,
+            member,
+            ty: field.ty.clone(),
+            input,
+        })
+    }
+
+    /// Generate an impl of `trait_` for the struct, requiring the forwarded field to implement it
+    fn impl_trait(&self, trait_: &TokenStream, body: TokenStream) -> TokenStream {
+        let name = &self.input.ident;
+        let ty = &self.ty;
+
+        let mut generics = self.input.generics.clone();
+        generics
+            .make_where_clause()
+            .predicates
+            .push(syn::parse_quote_spanned!(ty.span() => #ty: #trait_));
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+        quote!(
+            #[automatically_derived]
+            impl #impl_generics #trait_ for #name #ty_generics #where_clause {
+                #body
+            }
+        )
+    }
+}
+
+pub fn iterator(input: DeriveInput) -> syn::Result<TokenStream> {
+    let fwd = Forward::new(input, "Iterator")?;
+    let krate = &fwd.krate;
+    let ty = &fwd.ty;
+    let member = &fwd.member;
+    let a = gat_lifetime(&fwd.input.generics);
+
+    let trait_ = quote!(#krate::iter::Iterator);
+    Ok(fwd.impl_trait(
+        &trait_,
+        quote!(
+            type Item<#a> = <#ty as #trait_>::Item<#a>
+            where
+                Self: #a;
+
+            fn next(&mut self) -> ::core::option::Option<Self::Item<'_>> {
+                <#ty as #trait_>::next(&mut self.#member)
+            }
+
+            fn size_hint(&self) -> (usize, ::core::option::Option<usize>) {
+                <#ty as #trait_>::size_hint(&self.#member)
+            }
+
+            fn advance_by(&mut self, n: usize) -> ::core::result::Result<(), usize> {
+                <#ty as #trait_>::advance_by(&mut self.#member, n)
+            }
+
+            fn nth(&mut self, n: usize) -> ::core::option::Option<Self::Item<'_>> {
+                <#ty as #trait_>::nth(&mut self.#member, n)
+            }
+        ),
+    ))
+}
+
+pub fn double_ended_iterator(input: DeriveInput) -> syn::Result<TokenStream> {
+    let fwd = Forward::new(input, "DoubleEndedIterator")?;
+    let krate = &fwd.krate;
+    let ty = &fwd.ty;
+    let member = &fwd.member;
+
+    let trait_ = quote!(#krate::iter::DoubleEndedIterator);
+    Ok(fwd.impl_trait(
+        &trait_,
+        quote!(
+            fn next_back(
+                &mut self,
+            ) -> ::core::option::Option<<Self as #krate::iter::Iterator>::Item<'_>> {
+                <#ty as #trait_>::next_back(&mut self.#member)
+            }
+        ),
+    ))
+}
+
+pub fn index(input: DeriveInput) -> syn::Result<TokenStream> {
+    let fwd = Forward::new(input, "Index")?;
+    let krate = &fwd.krate;
+    let ty = &fwd.ty;
+    let member = &fwd.member;
+    let a = gat_lifetime(&fwd.input.generics);
+
+    Ok(fwd
+        .indices
+        .iter()
+        .map(|idx| {
+            let trait_ = quote!(#krate::ops::Index<#idx>);
+            fwd.impl_trait(
+                &trait_,
+                quote!(
+                    type Output<#a> = <#ty as #trait_>::Output<#a>
+                    where
+                        Self: #a;
+
+                    fn index(&self, idx: #idx) -> Self::Output<'_> {
+                        <#ty as #trait_>::index(&self.#member, idx)
+                    }
+                ),
+            )
+        })
+        .collect())
+}
+
+pub fn index_mut(input: DeriveInput) -> syn::Result<TokenStream> {
+    let fwd = Forward::new(input, "IndexMut")?;
+    let krate = &fwd.krate;
+    let ty = &fwd.ty;
+    let member = &fwd.member;
+    let a = gat_lifetime(&fwd.input.generics);
+
+    Ok(fwd
+        .indices
+        .iter()
+        .map(|idx| {
+            let trait_ = quote!(#krate::ops::IndexMut<#idx>);
+            fwd.impl_trait(
+                &trait_,
+                quote!(
+                    type OutputMut<#a> = <#ty as #trait_>::OutputMut<#a>
+                    where
+                        Self: #a;
+
+                    fn index_mut(&mut self, idx: #idx) -> Self::OutputMut<'_> {
+                        <#ty as #trait_>::index_mut(&mut self.#member, idx)
+                    }
+                ),
+            )
+        })
+        .collect())
+}
+
+pub fn _impl(
+    input: TokenStream,
+    derive: fn(DeriveInput) -> syn::Result<TokenStream>,
+) -> TokenStream {
+    syn::parse2::<DeriveInput>(input)
+        .and_then(derive)
+        .unwrap_or_else(|e| e.into_compile_error())
+}
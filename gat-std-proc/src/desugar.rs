@@ -1,97 +1,816 @@
-use std::error::Error;
-use proc_macro2::TokenStream;
-use quote::{quote_spanned, ToTokens};
-use syn::{Expr, ExprForLoop, ExprIndex, Item, Macro, Stmt};
+use proc_macro2::{Ident, Span, TokenStream, TokenTree};
+use quote::{quote, quote_spanned, ToTokens};
+use syn::{
+    Arm, Attribute, Block as SynBlock, Expr, ExprClosure, ExprForLoop, ExprIf, ExprIndex, ExprLet,
+    ExprRepeat, ExprUnary, ExprWhile, FnArg, GenericArgument, ImplItem, Item, Local, Macro, Pat,
+    PatIdent, PatType, Path, Signature, Stmt, Token, TraitItem, Type, TypeArray, UnOp, Variant,
+};
+use syn::parse::discouraged::Speculative;
 use syn::parse::{Parse, ParseStream, Parser};
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
 use syn::token::Comma;
+use syn::visit::Visit;
 use syn::visit_mut::VisitMut;
 
-struct Visitor {}
+use crate::options::{combine, is_gatify, is_skip, take_skip, Options};
+
+struct Visitor {
+    opts: Options,
+    errors: Vec<syn::Error>,
+    /// Whether the visited code must be const-evaluable, so can't call the GAT traits
+    in_const: bool,
+    /// The local bindings in scope
+    bindings: Vec<Binding>,
+    /// The `macro_rules!` defined in the visited code which are in scope
+    macros: Vec<Ident>,
+}
 
 impl Visitor {
-    fn new() -> Visitor {
-        Visitor {}
+    fn new(opts: Options) -> Visitor {
+        Visitor {
+            opts,
+            errors: Vec::new(),
+            in_const: false,
+            bindings: Vec::new(),
+            macros: Vec::new(),
+        }
+    }
+
+    /// Visit code with its own scope of bindings, such as a block or match arm
+    fn scoped(&mut self, f: impl FnOnce(&mut Visitor)) {
+        let (len, macros) = (self.bindings.len(), self.macros.len());
+        f(self);
+        self.bindings.truncate(len);
+        self.macros.truncate(macros);
+    }
+
+    /// Visit an item, which can't name the locals around it, but can use the macros
+    fn item_scope(&mut self, f: impl FnOnce(&mut Visitor)) {
+        let outer = std::mem::take(&mut self.bindings);
+        let macros = self.macros.len();
+        f(self);
+        self.bindings = outer;
+        self.macros.truncate(macros);
+    }
+
+    /// Bring the bindings of a pattern into scope
+    fn bind(&mut self, pat: &Pat) {
+        self.bind_with(|b| b.visit_pat(pat));
+    }
+
+    /// Bring the bindings of a typed argument into scope
+    fn bind_arg(&mut self, arg: &PatType) {
+        self.bind_with(|b| b.visit_pat_type(arg));
+    }
+
+    fn bind_with(&mut self, f: impl FnOnce(&mut Bindings)) {
+        let mut bindings = Bindings {
+            found: Vec::new(),
+            pointer: false,
+            unique: false,
+        };
+        f(&mut bindings);
+        self.bindings.extend(bindings.found);
+    }
+
+    /// Find the local binding an expression names, if any
+    fn binding(&self, expr: &Expr) -> Option<&Binding> {
+        let ident = match strip_parens(expr) {
+            Expr::Path(p) if p.qself.is_none() => p.path.get_ident()?,
+            _ => return None,
+        };
+        self.bindings.iter().rev().find(|b| b.ident == *ident)
+    }
+
+    /// Check whether an expression names a local binding not declared `mut`, which can't be
+    /// borrowed mutably even when it holds a `&mut T` that the core operator could reborrow
+    fn is_immutable_binding(&self, expr: &Expr) -> bool {
+        self.binding(expr).is_some_and(|b| !b.mutable)
+    }
+
+    /// Check whether a place starting at an expression can be borrowed mutably, as it's a binding
+    /// declared `mut` or known to hold a `&mut T`. Fields are looked through to their root.
+    fn is_mutable_root(&self, expr: &Expr) -> bool {
+        let mut root = strip_parens(expr);
+        while let Expr::Field(f) = root {
+            root = strip_parens(&f.base);
+        }
+        self.binding(root)
+            .is_some_and(|b| if b.pointer { b.unique } else { b.mutable })
+    }
+
+    /// Check whether an expression names a local binding known to hold a reference or raw
+    /// pointer. The core operator reborrows through these for as long as the original borrow,
+    /// rather than borrowing the binding itself, and raw pointers have no GAT impls.
+    fn is_pointer_binding(&self, expr: &Expr) -> bool {
+        self.binding(expr).is_some_and(|b| b.pointer)
+    }
+
+    /// Visit an expression in a place context, such as a `let` initializer or `match` scrutinee,
+    /// leaving any `*` on it to the core operator. Rewriting it would yield a place behind a
+    /// shared reference, which can't be moved out of or borrowed mutably by a pattern.
+    fn visit_place_mut(&mut self, expr: &mut Expr) {
+        match as_deref(expr) {
+            Some(u) => self.visit_expr_mut(&mut u.expr),
+            None => self.visit_expr_mut(expr),
+        }
+    }
+
+    /// Visit code which is or isn't in a const context
+    fn with_const(&mut self, in_const: bool, f: impl FnOnce(&mut Visitor)) {
+        let outer = std::mem::replace(&mut self.in_const, in_const);
+        f(self);
+        self.in_const = outer;
+    }
+
+    /// Check the attributes of some code, returning whether it should be visited. Code marked
+    /// `#[gatify::skip]` isn't, nor is code with a `#[gatify]` of its own, which is rewritten when
+    /// that attribute is expanded.
+    fn enter(&mut self, attrs: &mut Vec<Attribute>) -> bool {
+        !self.take_skip(attrs) && !attrs.iter().any(is_gatify)
+    }
+
+    /// Parse a rewritten expression. This should never fail, but if it does the error points at
+    /// the original expression instead of panicking.
+    fn parse_expr(&mut self, span: Span, ts: TokenStream) -> Expr {
+        match syn::parse2::<Expr>(ts.clone()) {
+            Ok(expr) => expr,
+            Err(e) => {
+                let msg = format!("gatify failed to rewrite this expression: {}", e);
+                self.errors.push(syn::Error::new(span, msg));
+                Expr::Verbatim(ts)
+            }
+        }
+    }
+
+    /// Remove any `#[gatify::skip]` from a list of attributes, returning whether the attributed
+    /// code should be skipped
+    fn take_skip(&mut self, attrs: &mut Vec<Attribute>) -> bool {
+        match take_skip(attrs) {
+            Ok(skip) => skip,
+            Err(e) => {
+                self.errors.push(e);
+                true
+            }
+        }
     }
 
     fn rewrite_for(&mut self, expr: &ExprForLoop) -> Expr {
+        let krate = &self.opts.krate;
+        let attrs = &expr.attrs;
+        let label = &expr.label;
         let pat = &expr.pat;
         let iter = &expr.expr;
         let body = &expr.body;
-        let new_expr: Expr = syn::parse2(quote_spanned!(
+
+        // Mixed-site locals can't shadow or be named by anything in the loop body
+        let into_iter = Ident::new("into_iter", Span::mixed_site());
+        let lending = Ident::new("iter", Span::mixed_site());
+
+        let next = quote_spanned!(pat.span() => Some(#pat) = #lending.next());
+        let ts = quote_spanned!(
             iter.span() =>
-            #[allow(unused_imports)]
+            #(#attrs)*
+            #[allow(unused_imports, unused_braces, clippy::while_let_on_iterator, clippy::redundant_pattern_matching)]
             {
-                use ::gat_std::__impl::{ViaLending, ViaCore};
-                use ::gat_std::iter::Iterator as _;
+                use #krate::__impl::{ViaLending, ViaCore, ViaNone};
+                use #krate::iter::Iterator as _;
                 use ::core::iter::Iterator as _;
 
-                let into_iter = ::gat_std::__impl::IntoIter(#iter);
-                let mut _iter = (&into_iter).select().into_iter(into_iter);
-                while let Some(#pat) = _iter.next() #body
+                let #into_iter = #krate::__impl::IntoIter(#iter);
+                // The block gives the argument the span of the iterated expression, so an
+                // unsupported type is reported there rather than at the attribute
+                let mut #lending = (&&#into_iter).select().into_iter({ #into_iter });
+                #label while let #next #body
             }
-        )).unwrap();
-        new_expr
+        );
+        self.parse_expr(iter.span(), ts)
     }
 
     fn rewrite_index(&mut self, expr: &ExprIndex) -> Expr {
-        syn::parse2::<Expr>(quote_spanned!(
-            expr.span() =>
-            compile_error!("GAT desugar requires index have an explicit `&` or `&mut`")
-        )).unwrap()
+        let krate = &self.opts.krate;
+        let val_expr = &expr.expr;
+        let idx_expr = &expr.index;
+
+        let ts = quote_spanned!(expr.span() =>
+            (*{
+                #[allow(unused_imports)]
+                use #krate::__impl::{ViaDeque, ViaLending, ViaCore, ViaRef, ViaNone};
+
+                match #krate::__impl::Index(&(#val_expr), #idx_expr) {
+                    val => (&&&&&&&&val).select().index_place(val),
+                }
+            })
+        );
+        self.parse_expr(expr.span(), ts)
+    }
+
+    /// Rewrite a method receiver made of field accesses and indexing, so each index selects
+    /// [`core::ops::IndexMut`] if it's implemented, and otherwise the GAT trait. This yields a
+    /// mutable place, which can be borrowed by a `&self` or `&mut self` method.
+    fn rewrite_receiver_mut(&mut self, expr: &mut Expr) -> Expr {
+        match expr {
+            Expr::Index(i) => {
+                let base = if is_index_path(&i.expr) {
+                    self.rewrite_receiver_mut(&mut i.expr).into_token_stream()
+                } else {
+                    self.visit_place_mut(&mut i.expr);
+                    let val_expr = &i.expr;
+                    // The target of a `&mut T` binding can be borrowed even if it isn't `mut`
+                    match self.binding(val_expr) {
+                        Some(b) if b.pointer => quote!(*#val_expr),
+                        _ => val_expr.to_token_stream(),
+                    }
+                };
+                self.visit_expr_mut(&mut i.index);
+
+                let krate = &self.opts.krate;
+                let idx_expr = &i.index;
+                let ts = quote_spanned!(i.span() =>
+                    (*{
+                        #[allow(unused_imports)]
+                        use #krate::__impl::{ViaLending, ViaCore, ViaRef, ViaNone};
+
+                        match #krate::__impl::IndexMut(&mut (#base), #idx_expr) {
+                            val => (&&&&val).select().index_place_mut(val),
+                        }
+                    })
+                );
+                self.parse_expr(i.span(), ts)
+            }
+            Expr::Field(f) => {
+                *f.base = self.rewrite_receiver_mut(&mut f.base);
+                Expr::Field(f.clone())
+            }
+            Expr::Paren(p) => {
+                *p.expr = self.rewrite_receiver_mut(&mut p.expr);
+                Expr::Paren(p.clone())
+            }
+            _ => unreachable!("receiver must be an index path"),
+        }
+    }
+
+    /// Rewrite a place made of field accesses and indexing, so each index calls
+    /// `Index::index` or `IndexMut::index_mut` on the step before it. This relies on method
+    /// resolution to borrow places, and to look through the references yielded by inner steps.
+    fn rewrite_path(&mut self, expr: &mut Expr, mutability: bool) -> TokenStream {
+        match expr {
+            Expr::Index(i) => {
+                let mut base = self.rewrite_path(&mut i.expr, mutability);
+                if !mutability && !is_index_path(&i.expr) {
+                    // A `VecDeque` can only be indexed by ranges through a selector
+                    let krate = &self.opts.krate;
+                    base = quote_spanned!(i.expr.span() => {
+                        #[allow(unused_imports)]
+                        use #krate::__impl::{ViaDeque, ViaCore};
+
+                        match #krate::__impl::Base(&(#base)) {
+                            val => (&val).select().index_base(val),
+                        }
+                    });
+                }
+                self.visit_expr_mut(&mut i.index);
+                let idx_expr = &i.index;
+                if mutability {
+                    quote_spanned!(i.span() => (#base).index_mut(#idx_expr))
+                } else {
+                    quote_spanned!(i.span() => (#base).index(#idx_expr))
+                }
+            }
+            Expr::Field(f) => {
+                let base = self.rewrite_path(&mut f.base, mutability);
+                let member = &f.member;
+                quote_spanned!(f.span() => (#base).#member)
+            }
+            Expr::Paren(p) => self.rewrite_path(&mut p.expr, mutability),
+            _ => {
+                self.visit_expr_mut(expr);
+                expr.to_token_stream()
+            }
+        }
+    }
+
+    /// Rewrite a borrow of a place containing an index. Borrowing an index directly yields the
+    /// output of the GAT trait, anything else borrows through it.
+    fn rewrite_ref_path(&mut self, expr: &mut Expr, mutability: bool) -> Expr {
+        let span = expr.span();
+        let path = self.rewrite_path(expr, mutability);
+        let path = match (strip_parens(expr), mutability) {
+            (Expr::Index(_), _) => path,
+            (_, true) => quote_spanned!(span => &mut #path),
+            (_, false) => quote_spanned!(span => &#path),
+        };
+
+        let krate = &self.opts.krate;
+        let ts = quote_spanned!(span => {
+            #[allow(unused_imports)]
+            use #krate::ops::{Index as _, IndexMut as _};
+
+            #path
+        });
+        self.parse_expr(span, ts)
     }
 
-    fn rewrite_ref_index(&mut self, expr: &ExprIndex, mutability: bool) -> Expr {
+    fn rewrite_deref(&mut self, expr: &ExprUnary) -> Expr {
+        let krate = &self.opts.krate;
+        let val_expr = &expr.expr;
+
+        let ts = quote_spanned!(expr.span() =>
+            *{
+                #[allow(unused_imports)]
+                use #krate::__impl::{ViaLending, ViaCore, ViaRef, ViaNone};
+
+                match #krate::__impl::Deref(&(#val_expr)) {
+                    val => (&&&val).select().deref_place(val),
+                }
+            }
+        );
+        self.parse_expr(expr.span(), ts)
+    }
+
+    fn rewrite_ref_deref(&mut self, expr: &ExprUnary, mutability: bool) -> Expr {
+        let krate = &self.opts.krate;
         let val_expr = &expr.expr;
-        let idx_expr = &expr.index;
 
         let ts = if mutability {
             quote_spanned!(expr.span() => {
-                ::gat_std::ops::IndexMut::index_mut(&mut (#val_expr), #idx_expr)
+                #[allow(unused_imports)]
+                use #krate::__impl::{ViaLending, ViaCore, ViaRef, ViaNone};
+
+                match #krate::__impl::DerefMut(&mut (#val_expr)) {
+                    val => (&&&val).select().deref_mut(val),
+                }
             })
         } else {
             quote_spanned!(expr.span() => {
-                ::gat_std::ops::Index::index(&(#val_expr), #idx_expr)
+                #[allow(unused_imports)]
+                use #krate::__impl::{ViaLending, ViaCore, ViaRef, ViaNone};
+
+                match #krate::__impl::Deref(&(#val_expr)) {
+                    val => (&&&val).select().deref(val),
+                }
             })
         };
 
-        syn::parse2::<Expr>(ts).unwrap()
+        self.parse_expr(expr.span(), ts)
     }
 
-    fn rewrite_assign_index(&mut self, expr: &ExprIndex) -> Expr {
-        let val_expr = &expr.expr;
-        let idx_expr = &expr.index;
+    fn rewrite_assign_path(&mut self, expr: &mut Expr) -> Expr {
+        let span = expr.span();
+        let place = self.rewrite_ref_path(expr, true);
+
+        self.parse_expr(span, quote_spanned!(span => *#place))
+    }
+}
+
+/// Rewrite the arguments of a macro taking a comma-separated list of expressions
+fn rewrite_macro_args<V: VisitMut>(v: &mut V, tokens: TokenStream) -> syn::Result<TokenStream> {
+    let mut args = Punctuated::<Expr, Comma>::parse_terminated.parse2(tokens)?;
+    for arg in &mut args {
+        v.visit_expr_mut(arg);
+    }
+    Ok(args.into_token_stream())
+}
+
+/// Rewrite the arguments of `vec!`, either a list of elements or `elem; len`
+fn rewrite_vec_args<V: VisitMut>(v: &mut V, tokens: TokenStream) -> syn::Result<TokenStream> {
+    let repeat = |input: ParseStream<'_>| {
+        let elem = input.parse::<Expr>()?;
+        let semi = input.parse::<Token![;]>()?;
+        let len = input.parse::<Expr>()?;
+        Ok((elem, semi, len))
+    };
+
+    match repeat.parse2(tokens.clone()) {
+        Ok((mut elem, semi, mut len)) => {
+            v.visit_expr_mut(&mut elem);
+            v.visit_expr_mut(&mut len);
+            Ok(quote!(#elem #semi #len))
+        }
+        Err(_) => rewrite_macro_args(v, tokens),
+    }
+}
+
+/// Rewrite the arguments of `matches!`. The pattern is left as-is, but the scrutinee and any
+/// guard are rewritten.
+fn rewrite_matches_args<V: VisitMut>(v: &mut V, tokens: TokenStream) -> syn::Result<TokenStream> {
+    let parts = |input: ParseStream<'_>| {
+        let scrutinee = input.parse::<Expr>()?;
+        let comma = input.parse::<Comma>()?;
+        let mut pat = TokenStream::new();
+        while !input.is_empty() && !input.peek(Token![if]) {
+            pat.extend(Some(input.parse::<TokenTree>()?));
+        }
+        let guard = if input.is_empty() {
+            None
+        } else {
+            let if_token = input.parse::<Token![if]>()?;
+            let guard = input.parse::<Expr>()?;
+            let comma = input.parse::<Option<Comma>>()?;
+            Some((if_token, guard, comma))
+        };
+        Ok((scrutinee, comma, pat, guard))
+    };
+
+    let (mut scrutinee, comma, pat, mut guard) = parts.parse2(tokens)?;
+    v.visit_expr_mut(&mut scrutinee);
+    let guard = guard.as_mut().map(|(if_token, guard, comma)| {
+        v.visit_expr_mut(guard);
+        quote!(#if_token #guard #comma)
+    });
+    Ok(quote!(#scrutinee #comma #pat #guard))
+}
+
+/// Rewrite the expressions in the arguments of a known `std` macro with a visitor. Unknown macros
+/// are left untouched, as their arguments may not be expressions, or they may care about their
+/// exact tokens.
+pub fn visit_known_macro<V: VisitMut>(v: &mut V, mac: &mut Macro) {
+    let name = match std_macro_name(&mac.path) {
+        Some(name) => name,
+        None => return,
+    };
+
+    let tokens = match name.as_str() {
+        "format" | "format_args" | "print" | "println" | "eprint" | "eprintln" | "write"
+        | "writeln" | "panic" | "unreachable" | "todo" | "unimplemented" | "assert"
+        | "debug_assert" | "assert_eq" | "assert_ne" | "debug_assert_eq" | "debug_assert_ne"
+        | "dbg" => {
+            rewrite_macro_args(v, mac.tokens.clone())
+        }
+        "vec" => rewrite_vec_args(v, mac.tokens.clone()),
+        "matches" => rewrite_matches_args(v, mac.tokens.clone()),
+        _ => return,
+    };
+
+    if let Ok(tokens) = tokens {
+        mac.tokens = tokens;
+    }
+}
+
+/// Get the name of a macro which may be from `std`, either a bare name or a path into `std`,
+/// `core` or `alloc`. Other paths name user macros, which may share a name with a `std` one.
+fn std_macro_name(path: &Path) -> Option<String> {
+    let first = &path.segments.first()?.ident;
+    let bare = path.segments.len() == 1 && path.leading_colon.is_none();
+    let in_std = path.segments.len() > 1 && (first == "std" || first == "core" || first == "alloc");
+    if bare || in_std {
+        path.segments.last().map(|seg| seg.ident.to_string())
+    } else {
+        None
+    }
+}
+
+/// Get the name defined by a `macro_rules!` item
+pub fn macro_rules_name(item: &Item) -> Option<&Ident> {
+    match item {
+        Item::Macro(item) if item.mac.path.is_ident("macro_rules") => item.ident.as_ref(),
+        _ => None,
+    }
+}
+
+/// Check whether a macro is named by a bare name defined by a `macro_rules!` in scope, which
+/// shadows any `std` macro of the same name. Only the definitions in the visited code can be seen.
+pub fn is_local_macro(path: &Path, macros: &[Ident]) -> bool {
+    path.get_ident().is_some_and(|name| macros.contains(name))
+}
+
+/// Get the attributes of an expression, if it can have any
+fn expr_attrs(expr: &mut Expr) -> Option<&mut Vec<Attribute>> {
+    match expr {
+        Expr::Array(e) => Some(&mut e.attrs),
+        Expr::Assign(e) => Some(&mut e.attrs),
+        Expr::AssignOp(e) => Some(&mut e.attrs),
+        Expr::Async(e) => Some(&mut e.attrs),
+        Expr::Await(e) => Some(&mut e.attrs),
+        Expr::Binary(e) => Some(&mut e.attrs),
+        Expr::Block(e) => Some(&mut e.attrs),
+        Expr::Box(e) => Some(&mut e.attrs),
+        Expr::Break(e) => Some(&mut e.attrs),
+        Expr::Call(e) => Some(&mut e.attrs),
+        Expr::Cast(e) => Some(&mut e.attrs),
+        Expr::Closure(e) => Some(&mut e.attrs),
+        Expr::Continue(e) => Some(&mut e.attrs),
+        Expr::Field(e) => Some(&mut e.attrs),
+        Expr::ForLoop(e) => Some(&mut e.attrs),
+        Expr::Group(e) => Some(&mut e.attrs),
+        Expr::If(e) => Some(&mut e.attrs),
+        Expr::Index(e) => Some(&mut e.attrs),
+        Expr::Let(e) => Some(&mut e.attrs),
+        Expr::Lit(e) => Some(&mut e.attrs),
+        Expr::Loop(e) => Some(&mut e.attrs),
+        Expr::Macro(e) => Some(&mut e.attrs),
+        Expr::Match(e) => Some(&mut e.attrs),
+        Expr::MethodCall(e) => Some(&mut e.attrs),
+        Expr::Paren(e) => Some(&mut e.attrs),
+        Expr::Path(e) => Some(&mut e.attrs),
+        Expr::Range(e) => Some(&mut e.attrs),
+        Expr::Reference(e) => Some(&mut e.attrs),
+        Expr::Repeat(e) => Some(&mut e.attrs),
+        Expr::Return(e) => Some(&mut e.attrs),
+        Expr::Struct(e) => Some(&mut e.attrs),
+        Expr::Try(e) => Some(&mut e.attrs),
+        Expr::TryBlock(e) => Some(&mut e.attrs),
+        Expr::Tuple(e) => Some(&mut e.attrs),
+        Expr::Type(e) => Some(&mut e.attrs),
+        Expr::Unary(e) => Some(&mut e.attrs),
+        Expr::Unsafe(e) => Some(&mut e.attrs),
+        Expr::While(e) => Some(&mut e.attrs),
+        Expr::Yield(e) => Some(&mut e.attrs),
+        _ => None,
+    }
+}
+
+/// Get the attributes of an item, if it can have any
+fn item_attrs(item: &mut Item) -> Option<&mut Vec<Attribute>> {
+    match item {
+        Item::Const(i) => Some(&mut i.attrs),
+        Item::Enum(i) => Some(&mut i.attrs),
+        Item::ExternCrate(i) => Some(&mut i.attrs),
+        Item::Fn(i) => Some(&mut i.attrs),
+        Item::ForeignMod(i) => Some(&mut i.attrs),
+        Item::Impl(i) => Some(&mut i.attrs),
+        Item::Macro(i) => Some(&mut i.attrs),
+        Item::Macro2(i) => Some(&mut i.attrs),
+        Item::Mod(i) => Some(&mut i.attrs),
+        Item::Static(i) => Some(&mut i.attrs),
+        Item::Struct(i) => Some(&mut i.attrs),
+        Item::Trait(i) => Some(&mut i.attrs),
+        Item::TraitAlias(i) => Some(&mut i.attrs),
+        Item::Type(i) => Some(&mut i.attrs),
+        Item::Union(i) => Some(&mut i.attrs),
+        Item::Use(i) => Some(&mut i.attrs),
+        _ => None,
+    }
+}
+
+/// A local binding in scope
+struct Binding {
+    ident: Ident,
+    /// Whether it's declared `mut`, so can be borrowed mutably
+    mutable: bool,
+    /// Whether it's known to hold a reference or raw pointer, from its type or initializer
+    pointer: bool,
+    /// Whether it's known to hold a `&mut T`, so its target can be borrowed mutably
+    unique: bool,
+}
+
+/// Collects the bindings of a pattern
+struct Bindings {
+    found: Vec<Binding>,
+    /// Whether the name being visited is typed as a reference or raw pointer
+    pointer: bool,
+    /// Whether the name being visited is typed as a `&mut T`
+    unique: bool,
+}
+
+impl<'ast> Visit<'ast> for Bindings {
+    fn visit_pat_ident(&mut self, pat: &'ast PatIdent) {
+        self.found.push(Binding {
+            ident: pat.ident.clone(),
+            mutable: pat.mutability.is_some() && pat.by_ref.is_none(),
+            pointer: std::mem::take(&mut self.pointer),
+            unique: std::mem::take(&mut self.unique),
+        });
+        syn::visit::visit_pat_ident(self, pat);
+    }
+
+    fn visit_pat_type(&mut self, pat: &'ast PatType) {
+        self.pointer = is_plain_ident(&pat.pat) && is_pointer_type(&pat.ty);
+        self.unique = is_plain_ident(&pat.pat) && is_unique_type(&pat.ty);
+        self.visit_pat(&pat.pat);
+        self.pointer = false;
+        self.unique = false;
+    }
+
+    // Paths and ranges in patterns can't bind anything
+    fn visit_expr(&mut self, _: &'ast Expr) {}
+}
+
+/// Check whether a pattern is a plain name, which holds the whole value
+fn is_plain_ident(pat: &Pat) -> bool {
+    matches!(pat, Pat::Ident(p) if p.by_ref.is_none() && p.subpat.is_none())
+}
+
+/// Check whether a type is a reference or raw pointer
+fn is_pointer_type(ty: &Type) -> bool {
+    match ty {
+        Type::Reference(_) | Type::Ptr(_) => true,
+        Type::Paren(p) => is_pointer_type(&p.elem),
+        Type::Group(g) => is_pointer_type(&g.elem),
+        _ => false,
+    }
+}
+
+/// Check whether a type is a mutable reference
+fn is_unique_type(ty: &Type) -> bool {
+    match ty {
+        Type::Reference(r) => r.mutability.is_some(),
+        Type::Paren(p) => is_unique_type(&p.elem),
+        Type::Group(g) => is_unique_type(&g.elem),
+        _ => false,
+    }
+}
+
+/// Look through any parentheses around an expression
+fn strip_parens(expr: &Expr) -> &Expr {
+    match expr {
+        Expr::Paren(p) => strip_parens(&p.expr),
+        _ => expr,
+    }
+}
+
+/// Check whether an expression is a place made of field accesses and indexing, with at least one
+/// index
+fn is_index_path(expr: &Expr) -> bool {
+    match expr {
+        Expr::Index(_) => true,
+        Expr::Field(f) => is_index_path(&f.base),
+        Expr::Paren(p) => is_index_path(&p.expr),
+        _ => false,
+    }
+}
+
+/// Get the container of the innermost index in a place made of field accesses and indexing,
+/// such as `a.b` in `a.b[i].c[j]`
+fn index_container(expr: &Expr) -> &Expr {
+    match expr {
+        Expr::Index(i) if is_index_path(&i.expr) => index_container(&i.expr),
+        Expr::Index(i) => &i.expr,
+        Expr::Field(f) => index_container(&f.base),
+        Expr::Paren(p) => index_container(&p.expr),
+        _ => expr,
+    }
+}
+
+/// Check whether an expression may use a place, or the binding it starts from as a whole. An
+/// access to a different field of the same binding doesn't count, but a method call does.
+fn uses_place(expr: &Expr, place: &Expr) -> bool {
+    // Groups are kept before their contents, so a method call's arguments can be recognized
+    fn flatten(ts: TokenStream, out: &mut Vec<TokenTree>) {
+        for tt in ts {
+            if let TokenTree::Group(g) = &tt {
+                out.push(tt.clone());
+                flatten(g.stream(), out);
+            } else {
+                out.push(tt);
+            }
+        }
+    }
+
+    let (mut tokens, mut path) = (Vec::new(), Vec::new());
+    flatten(expr.to_token_stream(), &mut tokens);
+    flatten(strip_parens(place).to_token_stream(), &mut path);
+    let same = |a: &TokenTree, b: &TokenTree| a.to_string() == b.to_string();
+    let Some(root) = path.first() else {
+        return false;
+    };
+
+    (0..tokens.len()).filter(|&i| same(&tokens[i], root)).any(|i| {
+        let rest = &tokens[i..];
+        let matched = rest.iter().zip(&path).take_while(|(a, b)| same(a, b)).count();
+        // The place is `a.b.c`, so the tokens diverge either after a name, where the binding or
+        // a field of the place is used as a whole, or at a different member after a `.`
+        let diverged_at_member = matched % 2 == 0;
+        let method_call = matches!(
+            rest.get(matched + 1),
+            Some(TokenTree::Group(g)) if g.delimiter() == proc_macro2::Delimiter::Parenthesis
+        );
+        matched == path.len() || !diverged_at_member || method_call
+    })
+}
 
-        syn::parse2::<Expr>(quote_spanned!(expr.span() =>
-            *::gat_std::ops::IndexMut::index_mut(&mut (#val_expr), #idx_expr)
-        )).unwrap()
+/// Get the operand of a dereference, looking through any parentheses
+fn as_deref(expr: &mut Expr) -> Option<&mut ExprUnary> {
+    match expr {
+        Expr::Unary(u) if matches!(u.op, UnOp::Deref(_)) => Some(u),
+        Expr::Paren(p) => as_deref(&mut p.expr),
+        _ => None,
     }
 }
 
 impl VisitMut for Visitor {
     fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        if let Some(attrs) = expr_attrs(expr) {
+            if self.take_skip(attrs) {
+                return;
+            }
+        }
+
+        if self.in_const {
+            // Closure bodies are the only code in a const context which runs at runtime
+            match expr {
+                Expr::Closure(c) => self.with_const(false, |v| v.visit_expr_closure_mut(c)),
+                _ => syn::visit_mut::visit_expr_mut(self, expr),
+            }
+            return;
+        }
+
         match expr {
-            Expr::ForLoop(f) => {
+            Expr::ForLoop(f) if self.opts.for_loops => {
                 *expr = self.rewrite_for(f);
             }
             Expr::Reference(r) => {
-                if let Expr::Index(i) = &*r.expr {
-                    *expr = self.rewrite_ref_index(i, r.mutability.is_some())
+                if self.opts.index && is_index_path(&r.expr) {
+                    *expr = self.rewrite_ref_path(&mut r.expr, r.mutability.is_some());
+                    return;
+                } else if let Expr::Unary(u) = &mut *r.expr {
+                    if matches!(u.op, UnOp::Deref(_)) && self.is_pointer_binding(&u.expr) {
+                        return;
+                    }
+                    if r.mutability.is_some() && self.is_immutable_binding(&u.expr) {
+                        // Only a `&mut T` can be reborrowed through an immutable binding, which
+                        // the core operator handles
+                        return;
+                    }
+                    if let (UnOp::Deref(_), true) = (u.op, self.opts.deref) {
+                        // Visit the operand first, as the rewritten expression contains
+                        // dereferences of its own
+                        self.visit_expr_mut(&mut u.expr);
+                        *expr = self.rewrite_ref_deref(u, r.mutability.is_some());
+                        return;
+                    }
                 }
             }
             Expr::Assign(a) => {
-                if let Expr::Index(i) = &*a.left {
-                    a.left = Box::new(self.rewrite_assign_index(i))
+                if self.opts.index && is_index_path(&a.left) {
+                    *a.left = self.rewrite_assign_path(&mut a.left);
+                    self.visit_expr_mut(&mut a.right);
+                    return;
+                } else if let Some(u) = as_deref(&mut a.left) {
+                    // Assignment through a dereference is left to the core operator
+                    self.visit_expr_mut(&mut u.expr);
+                    self.visit_expr_mut(&mut a.right);
+                    return;
                 }
             }
             Expr::AssignOp(a) => {
-                if let Expr::Index(i) = &*a.left {
-                    a.left = Box::new(self.rewrite_assign_index(i))
+                if self.opts.index && is_index_path(&a.left) {
+                    *a.left = self.rewrite_assign_path(&mut a.left);
+                    self.visit_expr_mut(&mut a.right);
+                    return;
+                } else if let Some(u) = as_deref(&mut a.left) {
+                    self.visit_expr_mut(&mut u.expr);
+                    self.visit_expr_mut(&mut a.right);
+                    return;
                 }
             }
-            Expr::Index(i) => {
+            Expr::MethodCall(m) => {
+                if self.opts.index && is_index_path(&m.receiver) {
+                    // Which trait a method needs can't be told from its call, so the receiver
+                    // is indexed mutably if its container can be borrowed mutably, and isn't
+                    // used by the arguments
+                    let mutable = self.is_mutable_root(index_container(&m.receiver))
+                        && !m.args.iter().any(|arg| uses_place(arg, index_container(&m.receiver)));
+                    if mutable {
+                        *m.receiver = self.rewrite_receiver_mut(&mut m.receiver);
+                    } else {
+                        self.visit_expr_mut(&mut m.receiver);
+                    }
+                } else if let Some(u) = as_deref(&mut m.receiver) {
+                    // Receivers may need a mutable place, so `*` is left to the core operator,
+                    // which picks `DerefMut` for a `&mut self` method
+                    self.visit_expr_mut(&mut u.expr);
+                } else {
+                    syn::visit_mut::visit_expr_method_call_mut(self, m);
+                    return;
+                }
+                for arg in &mut m.args {
+                    self.visit_expr_mut(arg);
+                }
+                return;
+            }
+            Expr::Field(f) => {
+                if let Some(u) = as_deref(&mut f.base) {
+                    self.visit_expr_mut(&mut u.expr);
+                    return;
+                }
+            }
+            Expr::Match(m) => {
+                for attr in &mut m.attrs {
+                    self.visit_attribute_mut(attr);
+                }
+                self.visit_place_mut(&mut m.expr);
+                for arm in &mut m.arms {
+                    self.visit_arm_mut(arm);
+                }
+                return;
+            }
+            Expr::Index(i) if self.opts.index => {
+                self.visit_expr_mut(&mut i.expr);
+                self.visit_expr_mut(&mut i.index);
                 *expr = self.rewrite_index(i);
+                return;
+            }
+            Expr::Unary(u) if self.opts.deref => {
+                if let UnOp::Deref(_) = u.op {
+                    if self.is_pointer_binding(&u.expr) {
+                        return;
+                    }
+                    self.visit_expr_mut(&mut u.expr);
+                    *expr = self.rewrite_deref(u);
+                    return;
+                }
             }
             _ => (),
         }
@@ -99,47 +818,291 @@ impl VisitMut for Visitor {
         syn::visit_mut::visit_expr_mut(self, expr);
     }
 
-    fn visit_macro_mut(&mut self, mac: &mut Macro) {
-        let args = (Punctuated::<Expr, Comma>::parse_separated_nonempty)
-            .parse2(mac.tokens.clone());
+    fn visit_item_mut(&mut self, item: &mut Item) {
+        // A macro is in scope from its definition to the end of the block
+        self.macros.extend(macro_rules_name(item).cloned());
+        if !item_attrs(item).is_none_or(|attrs| self.enter(attrs)) {
+            return;
+        }
+        let in_const = match item {
+            Item::Const(_) | Item::Static(_) => true,
+            Item::Fn(f) => f.sig.constness.is_some(),
+            _ => false,
+        };
+        self.item_scope(|v| v.with_const(in_const, |v| syn::visit_mut::visit_item_mut(v, item)));
+    }
+
+    fn visit_local_mut(&mut self, local: &mut Local) {
+        if self.take_skip(&mut local.attrs) {
+            return;
+        }
+        for attr in &mut local.attrs {
+            self.visit_attribute_mut(attr);
+        }
+        self.visit_pat_mut(&mut local.pat);
+        if let Some((_, init)) = &mut local.init {
+            self.visit_place_mut(init);
+        }
+        // The bindings are only in scope after the initializer
+        self.bind(&local.pat);
+        if let (Some((_, init)), Pat::Ident(_)) = (&local.init, &local.pat) {
+            if let (Expr::Reference(r), Some(b)) = (strip_parens(init), self.bindings.last_mut()) {
+                b.pointer = true;
+                b.unique = r.mutability.is_some();
+            }
+        }
+    }
+
+    fn visit_block_mut(&mut self, block: &mut SynBlock) {
+        self.scoped(|v| syn::visit_mut::visit_block_mut(v, block));
+    }
+
+    fn visit_signature_mut(&mut self, sig: &mut Signature) {
+        syn::visit_mut::visit_signature_mut(self, sig);
+        for input in &sig.inputs {
+            match input {
+                FnArg::Receiver(r) => {
+                    self.bindings.push(Binding {
+                        ident: Ident::new("self", r.self_token.span),
+                        mutable: r.mutability.is_some(),
+                        pointer: r.reference.is_some(),
+                        unique: r.reference.is_some() && r.mutability.is_some(),
+                    });
+                }
+                FnArg::Typed(t) => self.bind_arg(t),
+            }
+        }
+    }
 
-        if let Ok(mut args) = args {
-            for arg in &mut args {
-                self.visit_expr_mut(arg);
+    fn visit_expr_closure_mut(&mut self, expr: &mut ExprClosure) {
+        self.scoped(|v| {
+            for input in &expr.inputs {
+                v.bind(input);
             }
-            mac.tokens = args.into_token_stream();
+            syn::visit_mut::visit_expr_closure_mut(v, expr);
+        });
+    }
+
+    fn visit_expr_let_mut(&mut self, expr: &mut ExprLet) {
+        for attr in &mut expr.attrs {
+            self.visit_attribute_mut(attr);
+        }
+        self.visit_pat_mut(&mut expr.pat);
+        self.visit_place_mut(&mut expr.expr);
+        // Bound until the end of the enclosing `if` or `while` body
+        self.bind(&expr.pat);
+    }
+
+    fn visit_expr_if_mut(&mut self, expr: &mut ExprIf) {
+        for attr in &mut expr.attrs {
+            self.visit_attribute_mut(attr);
+        }
+        self.scoped(|v| {
+            v.visit_expr_mut(&mut expr.cond);
+            v.visit_block_mut(&mut expr.then_branch);
+        });
+        if let Some((_, else_branch)) = &mut expr.else_branch {
+            self.visit_expr_mut(else_branch);
+        }
+    }
+
+    fn visit_expr_while_mut(&mut self, expr: &mut ExprWhile) {
+        self.scoped(|v| syn::visit_mut::visit_expr_while_mut(v, expr));
+    }
+
+    fn visit_expr_for_loop_mut(&mut self, expr: &mut ExprForLoop) {
+        for attr in &mut expr.attrs {
+            self.visit_attribute_mut(attr);
+        }
+        self.visit_expr_mut(&mut expr.expr);
+        self.scoped(|v| {
+            v.visit_pat_mut(&mut expr.pat);
+            v.bind(&expr.pat);
+            v.visit_block_mut(&mut expr.body);
+        });
+    }
+
+    fn visit_impl_item_mut(&mut self, item: &mut ImplItem) {
+        let (attrs, in_const) = match item {
+            ImplItem::Const(i) => (&mut i.attrs, true),
+            ImplItem::Method(i) => (&mut i.attrs, i.sig.constness.is_some()),
+            ImplItem::Type(i) => (&mut i.attrs, false),
+            ImplItem::Macro(i) => (&mut i.attrs, false),
+            _ => return syn::visit_mut::visit_impl_item_mut(self, item),
+        };
+        if self.enter(attrs) {
+            self.item_scope(|v| {
+                v.with_const(in_const, |v| syn::visit_mut::visit_impl_item_mut(v, item))
+            });
+        }
+    }
+
+    fn visit_trait_item_mut(&mut self, item: &mut TraitItem) {
+        let (attrs, in_const) = match item {
+            TraitItem::Const(i) => (&mut i.attrs, true),
+            TraitItem::Method(i) => (&mut i.attrs, false),
+            TraitItem::Type(i) => (&mut i.attrs, false),
+            TraitItem::Macro(i) => (&mut i.attrs, false),
+            _ => return syn::visit_mut::visit_trait_item_mut(self, item),
+        };
+        if self.enter(attrs) {
+            self.item_scope(|v| {
+                v.with_const(in_const, |v| syn::visit_mut::visit_trait_item_mut(v, item))
+            });
+        }
+    }
+
+    fn visit_type_array_mut(&mut self, ty: &mut TypeArray) {
+        self.with_const(true, |v| syn::visit_mut::visit_type_array_mut(v, ty));
+    }
+
+    fn visit_expr_repeat_mut(&mut self, expr: &mut ExprRepeat) {
+        self.visit_expr_mut(&mut expr.expr);
+        self.with_const(true, |v| v.visit_expr_mut(&mut expr.len));
+    }
+
+    fn visit_generic_argument_mut(&mut self, arg: &mut GenericArgument) {
+        self.with_const(true, |v| syn::visit_mut::visit_generic_argument_mut(v, arg));
+    }
+
+    fn visit_variant_mut(&mut self, variant: &mut Variant) {
+        self.with_const(true, |v| syn::visit_mut::visit_variant_mut(v, variant));
+    }
+
+    fn visit_arm_mut(&mut self, arm: &mut Arm) {
+        if !self.take_skip(&mut arm.attrs) {
+            self.scoped(|v| {
+                v.bind(&arm.pat);
+                syn::visit_mut::visit_arm_mut(v, arm);
+            });
+        }
+    }
+
+    fn visit_attribute_mut(&mut self, attr: &mut Attribute) {
+        // Any skip attributes still here are somewhere they can't be handled
+        if is_skip(attr) {
+            self.errors.push(syn::Error::new_spanned(
+                &*attr,
+                "`#[gatify::skip]` can only be used on expressions, statements, items and match arms",
+            ));
+            // `gatify::skip` doesn't resolve to anything, so make it inert rather than reporting a
+            // second error for the same attribute
+            attr.path = syn::parse_quote_spanned!(attr.path.span() => allow);
+            attr.tokens = quote!((unused));
+        }
+    }
+
+    fn visit_macro_mut(&mut self, mac: &mut Macro) {
+        if self.opts.macros && !self.in_const && !is_local_macro(&mac.path, &self.macros) {
+            visit_known_macro(self, mac);
         }
     }
 }
 
-pub enum ItemOrStmt {
+/// The code `#[gatify]` is applied to
+pub enum Attributed {
     Item(Item),
+    ImplItem(ImplItem),
+    TraitItem(TraitItem),
     Stmt(Stmt),
 }
 
-impl Parse for ItemOrStmt {
+impl Parse for Attributed {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        input.parse::<Item>()
-            .map(ItemOrStmt::Item)
-            .or_else(|_| input.parse::<Stmt>().map(ItemOrStmt::Stmt))
+        // Associated items without a body, or with a `self` parameter, aren't valid items
+        fn attempt<T: Parse>(input: ParseStream, f: fn(T) -> Attributed) -> Option<Attributed> {
+            let fork = input.fork();
+            let out = fork.parse::<T>().ok().filter(|_| fork.is_empty())?;
+            input.advance_to(&fork);
+            Some(f(out))
+        }
+
+        attempt(input, Attributed::Item)
+            .or_else(|| attempt(input, Attributed::ImplItem))
+            .or_else(|| attempt(input, Attributed::TraitItem))
+            .map_or_else(|| input.parse::<Stmt>().map(Attributed::Stmt), Ok)
     }
 }
 
-impl ToTokens for ItemOrStmt {
+impl ToTokens for Attributed {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         match self {
-            ItemOrStmt::Item(i) => i.to_tokens(tokens),
-            ItemOrStmt::Stmt(s) => s.to_tokens(tokens),
+            Attributed::Item(i) => i.to_tokens(tokens),
+            Attributed::ImplItem(i) => i.to_tokens(tokens),
+            Attributed::TraitItem(i) => i.to_tokens(tokens),
+            Attributed::Stmt(s) => s.to_tokens(tokens),
         }
     }
 }
 
-pub fn _impl(val: TokenStream) -> Result<TokenStream, Box<dyn Error>> {
-    let mut is = syn::parse2::<ItemOrStmt>(val)?;
-    let mut visitor = Visitor::new();
-    match &mut is {
-        ItemOrStmt::Item(i) => visitor.visit_item_mut(i),
-        ItemOrStmt::Stmt(s) => visitor.visit_stmt_mut(s),
+/// The contents of `gatify_block!`, optionally starting with `#![gatify(..)]` arguments
+struct Block {
+    opts: Options,
+    stmts: Vec<Stmt>,
+}
+
+impl Parse for Block {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut opts = None;
+        for attr in input.call(Attribute::parse_inner)? {
+            if !is_gatify(&attr) {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "only `#![gatify(..)]` can be used at the start of `gatify_block!`",
+                ));
+            } else if opts.is_some() {
+                return Err(syn::Error::new_spanned(attr, "duplicate `#![gatify(..)]` arguments"));
+            }
+            opts = Some(if attr.tokens.is_empty() {
+                syn::parse2::<Options>(TokenStream::new())?
+            } else {
+                attr.parse_args::<Options>()?
+            });
+        }
+
+        let opts = match opts {
+            Some(opts) => opts,
+            None => syn::parse2::<Options>(TokenStream::new())?,
+        };
+        Ok(Block {
+            opts,
+            stmts: input.call(syn::Block::parse_within)?,
+        })
     }
-    Ok(is.into_token_stream())
+}
+
+/// Rewrite some code, emitting it alongside any errors, so the rest of it still type-checks
+fn rewrite(opts: Options, f: impl FnOnce(&mut Visitor)) -> TokenStream {
+    let mut visitor = Visitor::new(opts);
+    f(&mut visitor);
+    combine(visitor.errors).map_or_else(TokenStream::new, syn::Error::into_compile_error)
+}
+
+pub fn _impl(args: TokenStream, val: TokenStream) -> TokenStream {
+    let (opts, mut code) = match (syn::parse2::<Options>(args), syn::parse2::<Attributed>(val.clone())) {
+        (Ok(opts), Ok(code)) => (opts, code),
+        // Keep the original code on bad arguments, so it doesn't cause errors of its own
+        (Err(e), _) => return quote!(#val).into_iter().chain(e.into_compile_error()).collect(),
+        (_, Err(e)) => return e.into_compile_error(),
+    };
+    let errors = rewrite(opts, |v| match &mut code {
+        Attributed::Item(i) => v.visit_item_mut(i),
+        Attributed::ImplItem(i) => v.visit_impl_item_mut(i),
+        Attributed::TraitItem(i) => v.visit_trait_item_mut(i),
+        Attributed::Stmt(s) => v.visit_stmt_mut(s),
+    });
+    quote!(#code #errors)
+}
+
+pub fn _impl_block(val: TokenStream) -> TokenStream {
+    let Block { opts, mut stmts } = match syn::parse2::<Block>(val) {
+        Ok(block) => block,
+        Err(e) => return e.into_compile_error(),
+    };
+    let errors = rewrite(opts, |v| {
+        for stmt in &mut stmts {
+            v.visit_stmt_mut(stmt);
+        }
+    });
+    quote!(#(#stmts)* #errors)
 }
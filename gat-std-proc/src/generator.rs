@@ -0,0 +1,880 @@
+use std::collections::{HashMap, HashSet};
+
+use proc_macro2::{Literal, Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::parse::{Parse, ParseStream};
+use syn::spanned::Spanned;
+use syn::visit::Visit;
+use syn::visit_mut::VisitMut;
+use syn::{
+    Attribute, Block, Expr, ExprPath, FieldValue, GenericArgument, Ident, Item, Lifetime, Local,
+    Macro, Member, Pat, Path, Stmt, Token, Type,
+};
+
+use crate::desugar::{is_local_macro, macro_rules_name, visit_known_macro};
+use crate::lending::{Args, Elided};
+use crate::options::combine;
+
+/// The state of a finished iterator
+const DONE: usize = usize::MAX;
+
+/// A value moved into the iterator, as `name: Type` or `name: Type = value`
+struct Capture {
+    name: Ident,
+    ty: Type,
+    init: Expr,
+}
+
+impl Parse for Capture {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        input.parse::<Option<Token![mut]>>()?;
+        let name = input.parse::<Ident>()?;
+        input.parse::<Token![:]>()?;
+        let ty = input.parse::<Type>()?;
+        let init = if input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            input.parse::<Expr>()?
+        } else {
+            Expr::Path(ExprPath {
+                attrs: Vec::new(),
+                qself: None,
+                path: name.clone().into(),
+            })
+        };
+        Ok(Capture { name, ty, init })
+    }
+}
+
+/// The contents of `lending_iter!`
+struct Generator {
+    krate: Path,
+    captures: Vec<Capture>,
+    item: Type,
+    body: Block,
+}
+
+impl Parse for Generator {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut krate = None;
+        let mut errors = Vec::new();
+        for attr in input.call(Attribute::parse_inner)? {
+            if !attr.path.is_ident("lending_iter") {
+                errors.push(syn::Error::new_spanned(
+                    attr,
+                    "only `#![lending_iter(..)]` can be used at the start of `lending_iter!`",
+                ));
+            } else if krate.is_some() {
+                errors.push(syn::Error::new_spanned(
+                    attr,
+                    "duplicate `#![lending_iter(..)]` attribute",
+                ));
+            } else {
+                match attr.parse_args_with(Args::parse) {
+                    Ok(args) => krate = Some(args.krate),
+                    Err(e) => errors.push(e),
+                }
+            }
+        }
+        if let Some(e) = combine(errors) {
+            return Err(e);
+        }
+
+        input.parse::<Token![move]>()?;
+        let content;
+        syn::parenthesized!(content in input);
+        let captures = content.parse_terminated::<_, Token![,]>(Capture::parse)?;
+        input.parse::<Token![->]>()?;
+        let item = input.parse::<Type>()?;
+        let body = input.parse::<Block>()?;
+
+        Ok(Generator {
+            krate: krate.unwrap_or_else(|| syn::parse_quote!(::gat_std)),
+            captures: captures.into_iter().collect(),
+            item,
+            body,
+        })
+    }
+}
+
+/// Whether an expression or statement yields, outside of closures and items
+#[derive(Default)]
+struct Yields(bool);
+
+impl<'ast> Visit<'ast> for Yields {
+    fn visit_expr_yield(&mut self, _: &'ast syn::ExprYield) {
+        self.0 = true;
+    }
+
+    fn visit_expr_closure(&mut self, _: &'ast syn::ExprClosure) {}
+
+    fn visit_expr_async(&mut self, _: &'ast syn::ExprAsync) {}
+
+    fn visit_item(&mut self, _: &'ast Item) {}
+}
+
+fn expr_yields(expr: &Expr) -> bool {
+    let mut yields = Yields::default();
+    yields.visit_expr(expr);
+    yields.0
+}
+
+fn stmt_yields(stmt: &Stmt) -> bool {
+    let mut yields = Yields::default();
+    yields.visit_stmt(stmt);
+    yields.0
+}
+
+/// The names bound by a pattern
+#[derive(Default)]
+struct Bindings(Vec<String>);
+
+impl<'ast> Visit<'ast> for Bindings {
+    fn visit_pat_ident(&mut self, pat: &'ast syn::PatIdent) {
+        self.0.push(pat.ident.to_string());
+        syn::visit::visit_pat_ident(self, pat);
+    }
+
+    // Paths and ranges in patterns can't bind anything
+    fn visit_expr(&mut self, _: &'ast Expr) {}
+}
+
+fn bindings(pat: &Pat) -> HashSet<String> {
+    let mut names = Bindings::default();
+    names.visit_pat(pat);
+    names.0.into_iter().collect()
+}
+
+/// Where a name of the generator body is stored
+#[derive(Clone)]
+enum Binding {
+    Capture(Ident),
+    Local(Ident),
+}
+
+/// How a state ends
+enum Term {
+    Goto(usize),
+    Branch(Expr, usize, usize),
+    Yield(Expr, usize),
+    Done,
+}
+
+struct State {
+    stmts: Vec<TokenStream>,
+    term: Option<Term>,
+}
+
+/// A loop which was split into states
+struct Loop {
+    label: Option<Lifetime>,
+    head: usize,
+    exit: usize,
+    /// How many scopes are open outside the loop
+    depth: usize,
+}
+
+/// The names bound in a block of the generator
+#[derive(Default)]
+struct Scope {
+    names: HashMap<String, Binding>,
+    /// Fields of the locals declared in the block, in order
+    locals: Vec<Ident>,
+}
+
+/// Splits the generator body into states, which resume after each `yield`
+struct Lower {
+    krate: Path,
+    this: Ident,
+    dispatch: Lifetime,
+    states: Vec<State>,
+    cur: usize,
+    scopes: Vec<Scope>,
+    locals: Vec<(Ident, Type)>,
+    loops: Vec<Loop>,
+    items: Vec<Item>,
+    errors: Vec<syn::Error>,
+}
+
+impl Lower {
+    fn new_state(&mut self) -> usize {
+        self.states.push(State {
+            stmts: Vec::new(),
+            term: None,
+        });
+        self.states.len() - 1
+    }
+
+    /// End the current state. Code after a `break`, `continue` or `return` may try to end it a
+    /// second time, which is unreachable.
+    fn terminate(&mut self, term: Term) {
+        let state = &mut self.states[self.cur];
+        if state.term.is_none() {
+            state.term = Some(term);
+        }
+    }
+
+    fn lookup(&self, name: &str) -> Option<&Binding> {
+        self.scopes.iter().rev().find_map(|scope| scope.names.get(name))
+    }
+
+    /// Rewrite code which doesn't yield to run inside a state
+    fn rename(&mut self, f: impl FnOnce(&mut Rename<'_>)) {
+        let mut rename = Rename {
+            lower: self,
+            shadowed: Vec::new(),
+            labels: Vec::new(),
+            loop_depth: 0,
+            closure_depth: 0,
+            macros: self.items.iter().filter_map(macro_rules_name).cloned().collect(),
+            errors: Vec::new(),
+        };
+        f(&mut rename);
+        let errors = rename.errors;
+        self.errors.extend(errors);
+    }
+
+    fn error(&mut self, span: Span, msg: &str) {
+        self.errors.push(syn::Error::new(span, msg));
+    }
+
+    fn lower_block(&mut self, mut stmts: Vec<Stmt>) {
+        self.scopes.push(Scope::default());
+
+        // Everything after the last `yield` runs in one go, so it can be left as written
+        let split = stmts.iter().rposition(stmt_yields).map_or(0, |idx| idx + 1);
+        let rest = stmts.split_off(split);
+
+        for stmt in stmts {
+            self.lower_stmt(stmt);
+        }
+
+        if !rest.is_empty() {
+            let mut block = Block {
+                brace_token: Default::default(),
+                stmts: rest,
+            };
+            if let Some(last) = block.stmts.pop() {
+                block.stmts.push(match last {
+                    Stmt::Expr(expr) => Stmt::Semi(expr, Default::default()),
+                    other => other,
+                });
+            }
+            self.rename(|r| r.visit_block_mut(&mut block));
+            self.states[self.cur].stmts.push(quote!(#block));
+        }
+
+        let clear = self.clear(self.scopes.len() - 1);
+        self.states[self.cur].stmts.push(clear);
+        self.scopes.pop();
+    }
+
+    fn lower_stmt(&mut self, stmt: Stmt) {
+        match stmt {
+            Stmt::Local(local) => self.lower_local(local),
+            Stmt::Item(item) => self.items.push(item),
+            Stmt::Expr(expr) | Stmt::Semi(expr, _) => self.lower_expr(expr),
+        }
+    }
+
+    /// A `let` before a `yield` is stored in the iterator, so it needs a type
+    fn lower_local(&mut self, local: Local) {
+        let span = local.span();
+        let (name, ty) = match local.pat {
+            Pat::Type(pat) => match *pat.pat {
+                Pat::Ident(ident) if ident.by_ref.is_none() && ident.subpat.is_none() => {
+                    (ident.ident, *pat.ty)
+                }
+                other => {
+                    self.error(
+                        other.span(),
+                        "`let` before a `yield` can only bind a single name",
+                    );
+                    return;
+                }
+            },
+            _ => {
+                self.error(
+                    span,
+                    "`let` before a `yield` needs a type, as in `let name: Type = value;`",
+                );
+                return;
+            }
+        };
+        let mut init = match local.init {
+            Some((_, init)) if expr_yields(&init) => {
+                self.error(init.span(), "`yield` can only be used as a statement");
+                return;
+            }
+            Some((_, init)) => *init,
+            None => {
+                self.error(span, "`let` before a `yield` needs a value");
+                return;
+            }
+        };
+
+        // The value is evaluated before the name is in scope, as with a normal `let`
+        self.rename(|r| r.visit_expr_mut(&mut init));
+        let field = format_ident!("local_{}_{}", name, self.locals.len());
+        let this = &self.this;
+        self.states[self.cur]
+            .stmts
+            .push(quote!(#this.#field = ::core::option::Option::Some(#init);));
+        self.locals.push((field.clone(), ty));
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.names.insert(name.to_string(), Binding::Local(field.clone()));
+            scope.locals.push(field);
+        }
+    }
+
+    fn lower_expr(&mut self, mut expr: Expr) {
+        if !expr_yields(&expr) {
+            self.rename(|r| r.visit_expr_mut(&mut expr));
+            self.states[self.cur].stmts.push(quote!(#expr;));
+            return;
+        }
+
+        match expr {
+            Expr::Yield(expr) => {
+                let mut val = match expr.expr {
+                    Some(val) if expr_yields(&val) => {
+                        self.error(val.span(), "`yield` can only be used as a statement");
+                        return;
+                    }
+                    Some(val) => *val,
+                    None => {
+                        self.error(expr.yield_token.span, "`yield` needs a value");
+                        return;
+                    }
+                };
+                self.rename(|r| r.visit_expr_mut(&mut val));
+                let next = self.new_state();
+                self.terminate(Term::Yield(val, next));
+                self.cur = next;
+            }
+            Expr::Block(block) if block.label.is_none() => self.lower_block(block.block.stmts),
+            Expr::If(expr) => {
+                let mut cond = *expr.cond;
+                if matches!(cond, Expr::Let(_)) || expr_yields(&cond) {
+                    self.error(
+                        cond.span(),
+                        "`if let` and conditions containing `yield` aren't supported around a `yield`",
+                    );
+                    return;
+                }
+                self.rename(|r| r.visit_expr_mut(&mut cond));
+
+                let then = self.new_state();
+                let join = self.new_state();
+                let otherwise = match &expr.else_branch {
+                    Some(_) => self.new_state(),
+                    None => join,
+                };
+                self.terminate(Term::Branch(cond, then, otherwise));
+
+                self.cur = then;
+                self.lower_block(expr.then_branch.stmts);
+                self.terminate(Term::Goto(join));
+
+                if let Some((_, branch)) = expr.else_branch {
+                    self.cur = otherwise;
+                    self.lower_expr(*branch);
+                    self.terminate(Term::Goto(join));
+                }
+                self.cur = join;
+            }
+            Expr::Loop(expr) => {
+                let head = self.new_state();
+                let exit = self.new_state();
+                self.terminate(Term::Goto(head));
+
+                self.cur = head;
+                self.loops.push(Loop {
+                    label: expr.label.map(|label| label.name),
+                    head,
+                    exit,
+                    depth: self.scopes.len(),
+                });
+                self.lower_block(expr.body.stmts);
+                self.terminate(Term::Goto(head));
+                self.loops.pop();
+                self.cur = exit;
+            }
+            Expr::While(expr) => {
+                let mut cond = *expr.cond;
+                if matches!(cond, Expr::Let(_)) || expr_yields(&cond) {
+                    self.error(
+                        cond.span(),
+                        "`while let` and conditions containing `yield` aren't supported around a `yield`",
+                    );
+                    return;
+                }
+                self.rename(|r| r.visit_expr_mut(&mut cond));
+
+                let head = self.new_state();
+                let body = self.new_state();
+                let exit = self.new_state();
+                self.terminate(Term::Goto(head));
+
+                self.cur = head;
+                self.terminate(Term::Branch(cond, body, exit));
+
+                self.cur = body;
+                self.loops.push(Loop {
+                    label: expr.label.map(|label| label.name),
+                    head,
+                    exit,
+                    depth: self.scopes.len(),
+                });
+                self.lower_block(expr.body.stmts);
+                self.terminate(Term::Goto(head));
+                self.loops.pop();
+                self.cur = exit;
+            }
+            Expr::ForLoop(expr) => self.error(
+                expr.for_token.span,
+                "`for` loops can't contain a `yield`, as their iterator would need a type; use \
+                 `while` or `loop` with a typed `let` instead",
+            ),
+            other => self.error(
+                other.span(),
+                "`yield` can only be used as a statement, directly inside blocks, `if`, `loop` \
+                 and `while`",
+            ),
+        }
+    }
+
+    /// Drop the locals of the scopes from `depth` inwards, latest first as when leaving blocks.
+    /// Their names are only in scope while they are set.
+    fn clear(&self, depth: usize) -> TokenStream {
+        let this = &self.this;
+        let fields = self.scopes[depth..]
+            .iter()
+            .rev()
+            .flat_map(|scope| scope.locals.iter().rev());
+        quote!(#(#this.#fields = ::core::option::Option::None;)*)
+    }
+
+    /// The code for the end of a state
+    fn term(&self, term: &Term) -> TokenStream {
+        let this = &self.this;
+        let dispatch = &self.dispatch;
+        let lit = Literal::usize_unsuffixed;
+        match term {
+            Term::Goto(next) => {
+                let next = lit(*next);
+                quote!(#this.state = #next; continue #dispatch;)
+            }
+            Term::Branch(cond, then, otherwise) => {
+                let (then, otherwise) = (lit(*then), lit(*otherwise));
+                quote!(#this.state = if #cond { #then } else { #otherwise }; continue #dispatch;)
+            }
+            Term::Yield(val, next) => {
+                let next = lit(*next);
+                quote!(#this.state = #next; return ::core::option::Option::Some(#val);)
+            }
+            Term::Done => {
+                quote!(#this.state = #DONE; return ::core::option::Option::None;)
+            }
+        }
+    }
+}
+
+/// Rewrites code which doesn't yield to use the iterator's fields for names of the generator, and
+/// to move between states for `break`, `continue` and `return`
+struct Rename<'l> {
+    lower: &'l Lower,
+    /// Names bound inside the rewritten code
+    shadowed: Vec<HashSet<String>>,
+    /// Labels of loops and blocks inside the rewritten code
+    labels: Vec<Lifetime>,
+    /// How many loops inside the rewritten code an unlabeled `break` is in
+    loop_depth: usize,
+    closure_depth: usize,
+    /// The `macro_rules!` in scope, which shadow `std` macros
+    macros: Vec<Ident>,
+    errors: Vec<syn::Error>,
+}
+
+impl<'l> Rename<'l> {
+    fn binding(&self, name: &Ident) -> Option<Expr> {
+        let name_str = name.to_string();
+        if self.shadowed.iter().any(|scope| scope.contains(&name_str)) {
+            return None;
+        }
+        let krate = &self.lower.krate;
+        let this = &self.lower.this;
+        Some(match self.lower.lookup(&name_str)? {
+            Binding::Capture(field) => syn::parse_quote_spanned!(name.span() => #this.#field),
+            Binding::Local(field) => syn::parse_quote_spanned!(name.span() =>
+                (*#krate::__impl::local(&mut #this.#field))
+            ),
+        })
+    }
+
+    fn scoped(&mut self, names: HashSet<String>, f: impl FnOnce(&mut Self)) {
+        self.shadowed.push(names);
+        f(self);
+        self.shadowed.pop();
+    }
+
+    fn looped(&mut self, label: Option<&syn::Label>, f: impl FnOnce(&mut Self)) {
+        self.labels.extend(label.map(|label| label.name.clone()));
+        self.loop_depth += 1;
+        f(self);
+        self.loop_depth -= 1;
+        if label.is_some() {
+            self.labels.pop();
+        }
+    }
+
+    /// Find the split loop a `break` or `continue` leaves, if it doesn't target a loop inside the
+    /// rewritten code
+    fn target(&self, label: Option<&Lifetime>) -> Option<&'l Loop> {
+        if self.closure_depth > 0 {
+            return None;
+        }
+        match label {
+            Some(label) if self.labels.contains(label) => None,
+            Some(label) => self
+                .lower
+                .loops
+                .iter()
+                .rev()
+                .find(|l| l.label.as_ref() == Some(label)),
+            None if self.loop_depth > 0 => None,
+            None => self.lower.loops.last(),
+        }
+    }
+
+    /// Move to a state of the loop `target`, leaving the scopes inside it
+    fn goto(&self, target: &Loop, state: usize) -> Expr {
+        let this = &self.lower.this;
+        let dispatch = &self.lower.dispatch;
+        let clear = self.lower.clear(target.depth);
+        let state = Literal::usize_unsuffixed(state);
+        syn::parse_quote!({
+            #clear
+            #this.state = #state;
+            continue #dispatch;
+        })
+    }
+}
+
+impl VisitMut for Rename<'_> {
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        match expr {
+            Expr::Path(path) if path.qself.is_none() => {
+                if let Some(ident) = path.path.get_ident() {
+                    if let Some(new) = self.binding(ident) {
+                        *expr = new;
+                    }
+                }
+            }
+            Expr::Closure(closure) => {
+                let names = closure.inputs.iter().flat_map(bindings).collect();
+                self.closure_depth += 1;
+                self.scoped(names, |r| r.visit_expr_mut(&mut closure.body));
+                self.closure_depth -= 1;
+            }
+            Expr::Async(block) => {
+                self.closure_depth += 1;
+                self.visit_block_mut(&mut block.block);
+                self.closure_depth -= 1;
+            }
+            Expr::Block(block) => {
+                let label = block.label.as_ref();
+                self.labels.extend(label.map(|label| label.name.clone()));
+                self.visit_block_mut(&mut block.block);
+                if label.is_some() {
+                    self.labels.pop();
+                }
+            }
+            Expr::Loop(expr) => {
+                self.looped(expr.label.as_ref(), |r| r.visit_block_mut(&mut expr.body))
+            }
+            Expr::While(expr) => {
+                let names = match &mut *expr.cond {
+                    Expr::Let(cond) => {
+                        self.visit_expr_mut(&mut cond.expr);
+                        bindings(&cond.pat)
+                    }
+                    cond => {
+                        self.visit_expr_mut(cond);
+                        HashSet::new()
+                    }
+                };
+                self.scoped(names, |r| {
+                    r.looped(expr.label.as_ref(), |r| r.visit_block_mut(&mut expr.body))
+                });
+            }
+            Expr::ForLoop(expr) => {
+                self.visit_expr_mut(&mut expr.expr);
+                self.scoped(bindings(&expr.pat), |r| {
+                    r.looped(expr.label.as_ref(), |r| r.visit_block_mut(&mut expr.body))
+                });
+            }
+            Expr::If(expr) => {
+                let names = match &mut *expr.cond {
+                    Expr::Let(cond) => {
+                        self.visit_expr_mut(&mut cond.expr);
+                        bindings(&cond.pat)
+                    }
+                    cond => {
+                        self.visit_expr_mut(cond);
+                        HashSet::new()
+                    }
+                };
+                self.scoped(names, |r| r.visit_block_mut(&mut expr.then_branch));
+                if let Some((_, branch)) = &mut expr.else_branch {
+                    self.visit_expr_mut(branch);
+                }
+            }
+            Expr::Break(brk) => {
+                if let Some(target) = self.target(brk.label.as_ref()) {
+                    if let Some(val) = &brk.expr {
+                        self.errors.push(syn::Error::new_spanned(
+                            val,
+                            "loops containing a `yield` can't break with a value",
+                        ));
+                    }
+                    *expr = self.goto(target, target.exit);
+                } else if let Some(val) = &mut brk.expr {
+                    self.visit_expr_mut(val);
+                }
+            }
+            Expr::Continue(cont) => {
+                if let Some(target) = self.target(cont.label.as_ref()) {
+                    *expr = self.goto(target, target.head);
+                }
+            }
+            Expr::Return(ret) if self.closure_depth == 0 => {
+                if let Some(val) = &ret.expr {
+                    self.errors.push(syn::Error::new_spanned(
+                        val,
+                        "`lending_iter!` can't return a value, `yield` it instead",
+                    ));
+                }
+                let this = &self.lower.this;
+                let clear = self.lower.clear(0);
+                *expr = syn::parse_quote!({
+                    #clear
+                    #this.state = #DONE;
+                    return ::core::option::Option::None;
+                });
+            }
+            Expr::Yield(expr) => self.errors.push(syn::Error::new(
+                expr.yield_token.span,
+                "`yield` can only be used as a statement, directly inside blocks, `if`, `loop` \
+                 and `while`",
+            )),
+            _ => syn::visit_mut::visit_expr_mut(self, expr),
+        }
+    }
+
+    fn visit_block_mut(&mut self, block: &mut Block) {
+        self.shadowed.push(HashSet::new());
+        let macros = self.macros.len();
+        for stmt in &mut block.stmts {
+            match stmt {
+                Stmt::Local(local) => {
+                    if let Some((_, init)) = &mut local.init {
+                        self.visit_expr_mut(init);
+                    }
+                    let names = bindings(&local.pat);
+                    if let Some(scope) = self.shadowed.last_mut() {
+                        scope.extend(names);
+                    }
+                }
+                Stmt::Item(item) => self.macros.extend(macro_rules_name(item).cloned()),
+                Stmt::Expr(expr) | Stmt::Semi(expr, _) => self.visit_expr_mut(expr),
+            }
+        }
+        self.macros.truncate(macros);
+        self.shadowed.pop();
+    }
+
+    fn visit_arm_mut(&mut self, arm: &mut syn::Arm) {
+        self.scoped(bindings(&arm.pat), |r| {
+            if let Some((_, guard)) = &mut arm.guard {
+                r.visit_expr_mut(guard);
+            }
+            r.visit_expr_mut(&mut arm.body);
+        });
+    }
+
+    fn visit_field_value_mut(&mut self, field: &mut FieldValue) {
+        self.visit_expr_mut(&mut field.expr);
+        // A shorthand field needs its name back if its value was rewritten
+        if field.colon_token.is_none()
+            && matches!(field.member, Member::Named(_))
+            && !matches!(&field.expr, Expr::Path(path) if path.path.get_ident().is_some())
+        {
+            field.colon_token = Some(Default::default());
+        }
+    }
+
+    fn visit_macro_mut(&mut self, mac: &mut Macro) {
+        if !is_local_macro(&mac.path, &self.macros) {
+            visit_known_macro(self, mac);
+        }
+    }
+
+    // Names in patterns, types and generic arguments never refer to values of the generator
+    fn visit_pat_mut(&mut self, _: &mut Pat) {}
+
+    fn visit_type_mut(&mut self, _: &mut Type) {}
+
+    fn visit_generic_argument_mut(&mut self, _: &mut GenericArgument) {}
+
+    fn visit_item_mut(&mut self, _: &mut Item) {}
+}
+
+fn expand(gen: Generator) -> syn::Result<TokenStream> {
+    let Generator {
+        krate,
+        captures,
+        mut item,
+        body,
+    } = gen;
+
+    let mut errors = Vec::new();
+    let mut caps = HashMap::new();
+    for cap in &captures {
+        let field = format_ident!("cap_{}", cap.name);
+        if caps
+            .insert(cap.name.to_string(), Binding::Capture(field))
+            .is_some()
+        {
+            errors.push(syn::Error::new_spanned(
+                &cap.name,
+                format!("`{}` is captured more than once", cap.name),
+            ));
+        }
+    }
+
+    let mut lower = Lower {
+        krate: krate.clone(),
+        this: Ident::new("self", Span::mixed_site()),
+        dispatch: Lifetime {
+            apostrophe: Span::mixed_site(),
+            ident: Ident::new("dispatch", Span::mixed_site()),
+        },
+        states: Vec::new(),
+        cur: 0,
+        scopes: vec![Scope {
+            names: caps,
+            locals: Vec::new(),
+        }],
+        locals: Vec::new(),
+        loops: Vec::new(),
+        items: Vec::new(),
+        errors,
+    };
+    lower.new_state();
+    lower.lower_block(body.stmts);
+    lower.terminate(Term::Done);
+
+    if let Some(e) = combine(std::mem::take(&mut lower.errors)) {
+        return Err(e);
+    }
+
+    // Borrows held by the state become a lifetime of the iterator
+    let mut env = Elided {
+        receiver: None,
+        gat: Lifetime::new("'env", Span::mixed_site()),
+        used: false,
+    };
+    let mut cap_tys = captures
+        .iter()
+        .map(|cap| cap.ty.clone())
+        .collect::<Vec<_>>();
+    // Fields drop in order, so locals go first and latest first, as they would in a function
+    let mut local_tys = lower
+        .locals
+        .iter()
+        .rev()
+        .map(|(_, ty)| ty.clone())
+        .collect::<Vec<_>>();
+    for ty in cap_tys.iter_mut().chain(&mut local_tys) {
+        env.visit_type_mut(ty);
+    }
+    let generics = if env.used {
+        let env = &env.gat;
+        Some(quote!(<#env>))
+    } else {
+        None
+    };
+
+    let gat = Lifetime::new("'a", Span::mixed_site());
+    Elided {
+        receiver: None,
+        gat: gat.clone(),
+        used: false,
+    }
+    .visit_type_mut(&mut item);
+
+    let name = Ident::new("LendingIter", Span::mixed_site());
+    let this = &lower.this;
+    let dispatch = &lower.dispatch;
+    let cap_fields = captures
+        .iter()
+        .map(|cap| format_ident!("cap_{}", cap.name))
+        .collect::<Vec<_>>();
+    let cap_inits = captures.iter().map(|cap| &cap.init);
+    let local_fields = lower
+        .locals
+        .iter()
+        .rev()
+        .map(|(field, _)| field)
+        .collect::<Vec<_>>();
+    let items = &lower.items;
+    let arms = lower.states.iter().enumerate().map(|(idx, state)| {
+        let idx = Literal::usize_unsuffixed(idx);
+        let stmts = &state.stmts;
+        let term = lower.term(state.term.as_ref().unwrap_or(&Term::Done));
+        quote!(#idx => { #(#stmts)* #term })
+    });
+
+    Ok(quote!({
+        #[allow(dead_code)]
+        struct #name #generics {
+            state: usize,
+            #(#local_fields: ::core::option::Option<#local_tys>,)*
+            #(#cap_fields: #cap_tys,)*
+        }
+
+        impl #generics #krate::iter::Iterator for #name #generics {
+            type Item<#gat> = #item
+            where
+                Self: #gat;
+
+            #[allow(unreachable_code, unused_braces, unused_parens)]
+            fn next(&mut #this) -> ::core::option::Option<Self::Item<'_>> {
+                #(#items)*
+                #[allow(unused_labels)]
+                #dispatch: loop {
+                    match #this.state {
+                        #(#arms)*
+                        _ => return ::core::option::Option::None,
+                    }
+                }
+            }
+        }
+
+        #name {
+            state: 0,
+            #(#cap_fields: #cap_inits,)*
+            #(#local_fields: ::core::option::Option::None,)*
+        }
+    }))
+}
+
+pub fn _impl(val: TokenStream) -> TokenStream {
+    syn::parse2::<Generator>(val)
+        .and_then(expand)
+        .unwrap_or_else(|e| {
+            // Several errors are several macro calls, which need a block to be an expression
+            let e = e.into_compile_error();
+            quote!({ #e })
+        })
+}
@@ -0,0 +1,290 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::visit_mut::VisitMut;
+use syn::{
+    FnArg, GenericArgument, GenericParam, ImplItem, ImplItemMethod, ItemImpl, Lifetime, Path,
+    PathArguments, ReturnType, Token, Type, TypeBareFn, TypeReference, Visibility,
+};
+
+use crate::derive::gat_lifetime;
+use crate::options::combine;
+
+/// The arguments of `#[lending_iterator]`, also used by the `#![lending_iter(..)]` attribute of
+/// `lending_iter!`
+pub struct Args {
+    pub krate: Path,
+}
+
+impl Parse for Args {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut krate = syn::parse_quote!(::gat_std);
+        if !input.is_empty() {
+            input.parse::<Token![crate]>()?;
+            input.parse::<Token![=]>()?;
+            krate = Path::parse_mod_style(input)?;
+        }
+        Ok(Args { krate })
+    }
+}
+
+/// Replaces the elided lifetimes of a return type, and the lifetime of `self` if it's named, with
+/// the lifetime of the `Item` GAT
+pub struct Elided {
+    pub receiver: Option<Lifetime>,
+    pub gat: Lifetime,
+    /// Whether any lifetime was replaced
+    pub used: bool,
+}
+
+impl VisitMut for Elided {
+    fn visit_type_reference_mut(&mut self, ty: &mut TypeReference) {
+        if ty.lifetime.is_none() {
+            ty.lifetime = Some(self.gat.clone());
+            self.used = true;
+        }
+        syn::visit_mut::visit_type_reference_mut(self, ty);
+    }
+
+    fn visit_lifetime_mut(&mut self, lifetime: &mut Lifetime) {
+        if lifetime.ident == "_" || Some(&*lifetime) == self.receiver.as_ref() {
+            *lifetime = self.gat.clone();
+            self.used = true;
+        }
+    }
+
+    // Function pointers and `Fn` bounds have their own elision scope
+    fn visit_type_bare_fn_mut(&mut self, _: &mut TypeBareFn) {}
+
+    fn visit_parenthesized_generic_arguments_mut(
+        &mut self,
+        _: &mut syn::ParenthesizedGenericArguments,
+    ) {
+    }
+}
+
+/// Get the `T` of an `Option<T>` return type
+fn option_arg(output: &ReturnType) -> Option<&Type> {
+    let ty = match output {
+        ReturnType::Type(_, ty) => &**ty,
+        ReturnType::Default => return None,
+    };
+    let seg = match ty {
+        Type::Path(path) if path.qself.is_none() => path.path.segments.last()?,
+        _ => return None,
+    };
+    match &seg.arguments {
+        PathArguments::AngleBracketed(args) if seg.ident == "Option" && args.args.len() == 1 => {
+            match &args.args[0] {
+                GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Check that a method takes `&mut self` and nothing else, returning the lifetime of `self` if it's
+/// named
+fn check_receiver(method: &ImplItemMethod) -> syn::Result<Option<Lifetime>> {
+    let sig = &method.sig;
+    match sig.inputs.first() {
+        Some(FnArg::Receiver(recv)) if sig.inputs.len() == 1 => match &recv.reference {
+            Some((_, lifetime)) if recv.mutability.is_some() => Ok(lifetime.clone()),
+            _ => Err(syn::Error::new_spanned(recv, "expected `&mut self`")),
+        },
+        _ => Err(syn::Error::new_spanned(
+            &sig.inputs,
+            format!("`{}` should only take `&mut self`", sig.ident),
+        )),
+    }
+}
+
+/// Change a method returning an item to return `Self::Item`, as the trait expects. Returns the
+/// declared item type.
+fn lend(method: &mut ImplItemMethod, gat: &Lifetime) -> syn::Result<Type> {
+    let receiver = check_receiver(method)?;
+    let mut item = match option_arg(&method.sig.output) {
+        Some(ty) => ty.clone(),
+        None => {
+            let msg = format!("`{}` should return an `Option`", method.sig.ident);
+            return Err(syn::Error::new_spanned(&method.sig.output, msg));
+        }
+    };
+
+    Elided {
+        receiver: receiver.clone(),
+        gat: gat.clone(),
+        used: false,
+    }
+    .visit_type_mut(&mut item);
+
+    // A named lifetime on `self` is replaced by the elided one of the trait method
+    if let Some(receiver) = receiver {
+        let params = std::mem::take(&mut method.sig.generics.params);
+        method.sig.generics.params = params
+            .into_iter()
+            .filter(
+                |param| !matches!(param, GenericParam::Lifetime(def) if def.lifetime == receiver),
+            )
+            .collect();
+        if let Some(FnArg::Receiver(recv)) = method.sig.inputs.first_mut() {
+            if let Some((_, lifetime)) = &mut recv.reference {
+                *lifetime = None;
+            }
+        }
+    }
+    method.sig.output = syn::parse_quote!(-> ::core::option::Option<Self::Item<'_>>);
+
+    Ok(item)
+}
+
+fn expand(krate: &Path, mut imp: ItemImpl) -> syn::Result<TokenStream> {
+    if let Some((_, path, _)) = &imp.trait_ {
+        return Err(syn::Error::new_spanned(
+            path,
+            "`#[lending_iterator]` must be applied to an inherent impl block",
+        ));
+    }
+
+    let gat = gat_lifetime(&imp.generics);
+    let mut errors = Vec::new();
+    let mut item = None;
+    let mut forward = Vec::new();
+    let mut back = Vec::new();
+    let mut inherent = Vec::new();
+
+    for impl_item in std::mem::take(&mut imp.items) {
+        let mut method = match impl_item {
+            ImplItem::Method(method) => method,
+            other => {
+                inherent.push(other);
+                continue;
+            }
+        };
+
+        let name = method.sig.ident.to_string();
+        let lends = match name.as_str() {
+            "next" | "nth" | "next_back" => true,
+            "size_hint" | "advance_by" => false,
+            _ => {
+                inherent.push(ImplItem::Method(method));
+                continue;
+            }
+        };
+
+        if lends {
+            // `nth` has an extra argument, only its return type matters
+            if name == "nth" {
+                method.sig.output = syn::parse_quote!(-> ::core::option::Option<Self::Item<'_>>);
+            } else {
+                let ty = lend(&mut method, &gat);
+                if name == "next" {
+                    item = Some(ty);
+                } else if let Err(e) = ty {
+                    errors.push(e);
+                }
+            }
+        }
+
+        // Trait methods can't have a visibility
+        method.vis = Visibility::Inherited;
+        if name == "next_back" {
+            back.push(method);
+        } else {
+            forward.push(method);
+        }
+    }
+
+    let item = item.unwrap_or_else(|| {
+        Err(syn::Error::new_spanned(
+            &imp.self_ty,
+            "`#[lending_iterator]` needs a `fn next(&mut self) -> Option<..>` to infer the item from",
+        ))
+    });
+    let item = match (item, combine(errors)) {
+        (Ok(item), None) => item,
+        (Ok(_), Some(e)) => return Err(e),
+        (Err(mut e), rest) => {
+            e.extend(rest);
+            return Err(e);
+        }
+    };
+
+    let attrs = &imp.attrs;
+    let self_ty = &imp.self_ty;
+    let (impl_generics, _, where_clause) = imp.generics.split_for_impl();
+
+    let back = if back.is_empty() {
+        None
+    } else {
+        Some(quote!(
+            #(#attrs)*
+            impl #impl_generics #krate::iter::DoubleEndedIterator for #self_ty #where_clause {
+                #(#back)*
+            }
+        ))
+    };
+    let inherent = if inherent.is_empty() {
+        None
+    } else {
+        Some(quote!(
+            #(#attrs)*
+            impl #impl_generics #self_ty #where_clause {
+                #(#inherent)*
+            }
+        ))
+    };
+
+    Ok(quote!(
+        #(#attrs)*
+        impl #impl_generics #krate::iter::Iterator for #self_ty #where_clause {
+            type Item<#gat> = #item
+            where
+                Self: #gat;
+
+            #(#forward)*
+        }
+
+        #back
+        #inherent
+    ))
+}
+
+pub fn _impl(args: TokenStream, val: TokenStream) -> TokenStream {
+    let args = match syn::parse2::<Args>(args) {
+        Ok(args) => args,
+        Err(e) => {
+            return quote!(#val)
+                .into_iter()
+                .chain(e.into_compile_error())
+                .collect()
+        }
+    };
+    let imp = match syn::parse2::<ItemImpl>(val.clone()) {
+        Ok(imp) => imp,
+        Err(_) => {
+            // A method alone doesn't name the type to implement the trait for
+            let msg = match syn::parse2::<ImplItemMethod>(val.clone()) {
+                Ok(method) if method.sig.ident == "next" => {
+                    "`#[lending_iterator]` must be applied to the impl block containing `next`, \
+                     not to `next` itself"
+                }
+                _ => "`#[lending_iterator]` must be applied to the impl block containing `next`",
+            };
+            let e = syn::Error::new_spanned(&val, msg);
+            return quote!(#val)
+                .into_iter()
+                .chain(e.into_compile_error())
+                .collect();
+        }
+    };
+    // Keep the original code on errors, so uses of its methods don't cause errors of their own
+    match expand(&args.krate, imp) {
+        Ok(ts) => ts,
+        Err(e) => quote!(#val)
+            .into_iter()
+            .chain(e.into_compile_error())
+            .collect(),
+    }
+}
@@ -1,20 +1,48 @@
 
 use proc_macro::TokenStream;
-use quote::quote;
 
+mod derive;
 mod desugar;
+mod generator;
+mod lending;
+mod options;
 
 #[proc_macro_attribute]
 pub fn gatify(args: TokenStream, val: TokenStream) -> TokenStream {
-    if !args.is_empty() {
-        return quote!(compile_error!("gatify takes no arguments");).into();
-    }
-
-    match desugar::_impl(val.into()) {
-        Ok(ts) => ts.into(),
-        Err(e) => {
-            let err = e.to_string();
-            quote!(compile_error!(#err);).into()
-        }
-    }
+    desugar::_impl(args.into(), val.into()).into()
+}
+
+#[proc_macro]
+pub fn gatify_block(val: TokenStream) -> TokenStream {
+    desugar::_impl_block(val.into()).into()
+}
+
+#[proc_macro_attribute]
+pub fn lending_iterator(args: TokenStream, val: TokenStream) -> TokenStream {
+    lending::_impl(args.into(), val.into()).into()
+}
+
+#[proc_macro]
+pub fn lending_iter(val: TokenStream) -> TokenStream {
+    generator::_impl(val.into()).into()
+}
+
+#[proc_macro_derive(Iterator, attributes(gat_std))]
+pub fn derive_iterator(input: TokenStream) -> TokenStream {
+    derive::_impl(input.into(), derive::iterator).into()
+}
+
+#[proc_macro_derive(DoubleEndedIterator, attributes(gat_std))]
+pub fn derive_double_ended_iterator(input: TokenStream) -> TokenStream {
+    derive::_impl(input.into(), derive::double_ended_iterator).into()
+}
+
+#[proc_macro_derive(Index, attributes(gat_std))]
+pub fn derive_index(input: TokenStream) -> TokenStream {
+    derive::_impl(input.into(), derive::index).into()
+}
+
+#[proc_macro_derive(IndexMut, attributes(gat_std))]
+pub fn derive_index_mut(input: TokenStream) -> TokenStream {
+    derive::_impl(input.into(), derive::index_mut).into()
 }
@@ -0,0 +1,154 @@
+use proc_macro2::Ident;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::token::Comma;
+use syn::{parenthesized, Attribute, Path, Token};
+
+const TOGGLES: &str = "`for_loops`, `index`, `deref` or `macros`";
+
+/// The arguments of `#[gatify]`
+pub struct Options {
+    /// The path to the `gat_std` crate
+    pub krate: Path,
+    /// Whether to rewrite `for` loops
+    pub for_loops: bool,
+    /// Whether to rewrite indexing
+    pub index: bool,
+    /// Whether to rewrite dereferences
+    pub deref: bool,
+    /// Whether to rewrite the arguments of known macros
+    pub macros: bool,
+}
+
+impl Options {
+    fn toggle(&mut self, name: &Ident) -> syn::Result<&mut bool> {
+        match name.to_string().as_str() {
+            "for_loops" => Ok(&mut self.for_loops),
+            "index" => Ok(&mut self.index),
+            "deref" => Ok(&mut self.deref),
+            "macros" => Ok(&mut self.macros),
+            _ => Err(syn::Error::new(
+                name.span(),
+                format!("unknown gatify toggle `{}`, expected {}", name, TOGGLES),
+            )),
+        }
+    }
+}
+
+enum Arg {
+    Crate(Path),
+    Enable(Ident),
+    Skip(Punctuated<Ident, Comma>),
+}
+
+impl Parse for Arg {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(Token![crate]) {
+            input.parse::<Token![crate]>()?;
+            input.parse::<Token![=]>()?;
+            return Path::parse_mod_style(input).map(Arg::Crate);
+        }
+
+        let name = input.parse::<Ident>()?;
+        if name == "skip" && input.peek(syn::token::Paren) {
+            let content;
+            parenthesized!(content in input);
+            Ok(Arg::Skip(content.parse_terminated(Ident::parse)?))
+        } else {
+            Ok(Arg::Enable(name))
+        }
+    }
+}
+
+impl Parse for Options {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let args = input.parse_terminated::<Arg, Comma>(Arg::parse)?;
+
+        // Listing any toggles enables only those, otherwise everything is enabled
+        let all = !args.iter().any(|arg| matches!(arg, Arg::Enable(_)));
+        let mut out = Options {
+            krate: syn::parse_quote!(::gat_std),
+            for_loops: all,
+            index: all,
+            deref: all,
+            macros: all,
+        };
+
+        let mut krate = None;
+        let mut errors = Vec::new();
+        for arg in args {
+            match arg {
+                Arg::Crate(path) => {
+                    if krate.replace(()).is_some() {
+                        errors.push(syn::Error::new_spanned(path, "duplicate `crate` argument"));
+                    } else {
+                        out.krate = path;
+                    }
+                }
+                Arg::Enable(name) => match out.toggle(&name) {
+                    Ok(toggle) => *toggle = true,
+                    Err(e) => errors.push(e),
+                },
+                Arg::Skip(names) => {
+                    for name in names {
+                        match out.toggle(&name) {
+                            Ok(toggle) => *toggle = false,
+                            Err(e) => errors.push(e),
+                        }
+                    }
+                }
+            }
+        }
+
+        match combine(errors) {
+            Some(e) => Err(e),
+            None => Ok(out),
+        }
+    }
+}
+
+/// Check whether an attribute is `#[gatify]`, possibly through a path
+pub fn is_gatify(attr: &Attribute) -> bool {
+    attr.path.segments.last().is_some_and(|seg| seg.ident == "gatify")
+}
+
+/// Check whether an attribute is `#[gatify::skip]`
+pub fn is_skip(attr: &Attribute) -> bool {
+    let mut segments = attr.path.segments.iter().rev();
+    matches!(
+        (segments.next(), segments.next()),
+        (Some(skip), Some(gatify)) if skip.ident == "skip" && gatify.ident == "gatify"
+    )
+}
+
+/// Remove any `#[gatify::skip]` from a list of attributes, returning whether one was found. Errors
+/// if a skip attribute is given arguments.
+pub fn take_skip(attrs: &mut Vec<Attribute>) -> syn::Result<bool> {
+    let mut found = false;
+    let mut errors = Vec::new();
+    attrs.retain(|attr| {
+        if !is_skip(attr) {
+            return true;
+        }
+        if !attr.tokens.is_empty() {
+            errors.push(syn::Error::new_spanned(
+                &attr.tokens,
+                "`#[gatify::skip]` takes no arguments",
+            ));
+        }
+        found = true;
+        false
+    });
+    match combine(errors) {
+        Some(e) => Err(e),
+        None => Ok(found),
+    }
+}
+
+/// Combine a list of errors into one, so they are all reported
+pub fn combine(errors: impl IntoIterator<Item = syn::Error>) -> Option<syn::Error> {
+    errors.into_iter().reduce(|mut acc, e| {
+        acc.combine(e);
+        acc
+    })
+}
@@ -0,0 +1,136 @@
+//! GAT equivalent of `std` async iterator traits, often referred to as a lending stream
+
+mod adapters;
+
+pub use adapters::*;
+
+use core::marker::PhantomData;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use crate::iter::Lend;
+
+/// The items of an [`AsyncIterator`], or their absence, so they can use
+/// [`change_lifetime`](crate::iter::change_lifetime)
+struct AsyncItems<I: ?Sized>(PhantomData<I>);
+
+impl<I: ?Sized + AsyncIterator> Lend for AsyncItems<I> {
+    type Lent<'a> = Option<I::Item<'a>>
+    where
+        Self: 'a;
+}
+
+/// An async lending iterator, whose items may have their lifetimes tied to the individual borrow
+/// of the iterator. This allows for things like a socket reader lending slices of its internal
+/// buffer, with the trade-off that the items of this iterator cannot co-exist.
+pub trait AsyncIterator {
+    /// The value yielded by each successful call to `poll_next` on this iterator
+    type Item<'a>
+    where
+        Self: 'a;
+
+    /// Attempt to get the next value of this iterator, registering the current task for wakeup
+    /// if the value is not yet available. Returns `Poll::Ready(None)` once the iterator is
+    /// exhausted.
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item<'_>>>;
+
+    /// Get a hint as to the size of this iterator - the first value is a lower bound, second
+    /// is an optional upper bound.
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, None)
+    }
+
+    /// Get a future which resolves to the next value of this iterator, or `None`
+    fn next(&mut self) -> Next<'_, Self>
+    where
+        Self: Unpin,
+    {
+        Next::new(self)
+    }
+
+    // Lazy Adaptors
+
+    /// Gain mutable access to each value in this iterator, then yield it to the next step.
+    /// This allows altering each item without consuming it, preserving the lending nature
+    /// or the iterator
+    fn touch<F>(self, f: F) -> Touch<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(&mut Self::Item<'_>),
+    {
+        Touch::new(self, f)
+    }
+
+    /// Execute a closure on each item in the iterator, returning true if it should be included, or
+    /// false to skip it
+    fn filter<F>(self, f: F) -> Filter<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(&Self::Item<'_>) -> bool,
+    {
+        Filter::new(self, f)
+    }
+
+    /// Creates an iterator which gives the current iteration count as well as the next value
+    fn enumerate(self) -> Enumerate<Self>
+    where
+        Self: Sized,
+    {
+        Enumerate::new(self)
+    }
+
+    /// Creates an iterator that yields the first n elements, or fewer if the underlying iterator
+    /// ends sooner
+    fn take(self, n: usize) -> Take<Self>
+    where
+        Self: Sized,
+    {
+        Take::new(self, n)
+    }
+
+    // Consumers
+
+    /// Get a future which executes a closure on each value of this iterator
+    fn for_each<F>(self, f: F) -> ForEach<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(Self::Item<'_>),
+    {
+        ForEach::new(self, f)
+    }
+}
+
+impl<I: ?Sized + AsyncIterator + Unpin> AsyncIterator for &mut I {
+    type Item<'a> = I::Item<'a>
+    where
+        Self: 'a;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item<'_>>> {
+        Pin::new(&mut **self.get_mut()).poll_next(cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (**self).size_hint()
+    }
+}
+
+/// Trait for converting a lending iterator into an async lending iterator which is always ready.
+///
+/// This is useful for testing, or for providing in-memory data to code which expects an
+/// [`AsyncIterator`].
+pub trait IntoAsync: Sized {
+    /// Convert this iterator into an async one
+    fn into_async(self) -> FromIter<Self>;
+}
+
+impl<I> IntoAsync for I
+where
+    I: crate::iter::Iterator,
+{
+    fn into_async(self) -> FromIter<Self> {
+        FromIter(self)
+    }
+}
+
+#[cfg(test)]
+mod test;
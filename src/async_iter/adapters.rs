@@ -0,0 +1,290 @@
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use super::{AsyncItems, AsyncIterator};
+use crate::iter::{change_lifetime, Iterator};
+
+/// See [`IntoAsync::into_async`](super::IntoAsync::into_async)
+pub struct FromIter<I>(pub(crate) I);
+
+// The inner iterator is never pinned
+impl<I> Unpin for FromIter<I> {}
+
+impl<I> AsyncIterator for FromIter<I>
+where
+    I: Iterator,
+{
+    type Item<'a> = I::Item<'a>
+    where
+        Self: 'a;
+
+    fn poll_next(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<Self::Item<'_>>> {
+        Poll::Ready(self.get_mut().0.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+/// See [`AsyncIterator::next`]
+pub struct Next<'a, I: ?Sized> {
+    iter: Option<&'a mut I>,
+}
+
+impl<'a, I: ?Sized> Next<'a, I> {
+    pub(crate) fn new(iter: &'a mut I) -> Next<'a, I> {
+        Next { iter: Some(iter) }
+    }
+}
+
+impl<'a, I> Future for Next<'a, I>
+where
+    I: ?Sized + AsyncIterator + Unpin,
+{
+    type Output = Option<I::Item<'a>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let iter = this.iter.take().expect("`Next` polled after completion");
+        let out = Pin::new(&mut *iter)
+            .poll_next(cx)
+            // SAFETY: This is the polonius case - `iter` is only given back if nothing was lent
+            .map(|val| unsafe { change_lifetime::<AsyncItems<I>>(val) });
+        if out.is_pending() {
+            this.iter = Some(iter);
+        }
+        out
+    }
+}
+
+/// See [`AsyncIterator::touch`]
+pub struct Touch<I, F> {
+    iter: I,
+    func: F,
+}
+
+impl<I, F> Touch<I, F> {
+    pub(crate) fn new(iter: I, func: F) -> Touch<I, F> {
+        Touch { iter, func }
+    }
+
+    fn project(self: Pin<&mut Self>) -> (Pin<&mut I>, &mut F) {
+        // SAFETY: `iter` is structurally pinned and never moved out of, `func` is never pinned
+        unsafe {
+            let this = self.get_unchecked_mut();
+            (Pin::new_unchecked(&mut this.iter), &mut this.func)
+        }
+    }
+}
+
+impl<I, F> AsyncIterator for Touch<I, F>
+where
+    I: AsyncIterator,
+    F: FnMut(&mut I::Item<'_>),
+{
+    type Item<'a> = I::Item<'a>
+    where
+        Self: 'a;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item<'_>>> {
+        let (iter, func) = self.project();
+        match iter.poll_next(cx) {
+            Poll::Ready(Some(mut val)) => {
+                func(&mut val);
+                Poll::Ready(Some(val))
+            }
+            other => other,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// See [`AsyncIterator::filter`]
+pub struct Filter<I, F> {
+    iter: I,
+    func: F,
+}
+
+impl<I, F> Filter<I, F> {
+    pub(crate) fn new(iter: I, func: F) -> Filter<I, F> {
+        Filter { iter, func }
+    }
+
+    fn project(self: Pin<&mut Self>) -> (Pin<&mut I>, &mut F) {
+        // SAFETY: `iter` is structurally pinned and never moved out of, `func` is never pinned
+        unsafe {
+            let this = self.get_unchecked_mut();
+            (Pin::new_unchecked(&mut this.iter), &mut this.func)
+        }
+    }
+}
+
+impl<I, F> AsyncIterator for Filter<I, F>
+where
+    I: AsyncIterator,
+    F: FnMut(&I::Item<'_>) -> bool,
+{
+    type Item<'a> = I::Item<'a>
+    where
+        Self: 'a;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item<'_>>> {
+        let (mut iter, func) = self.project();
+        loop {
+            match iter.as_mut().poll_next(cx) {
+                Poll::Ready(Some(val)) => {
+                    if func(&val) {
+                        // SAFETY: This is the polonius case
+                        return Poll::Ready(unsafe { change_lifetime::<AsyncItems<I>>(Some(val)) });
+                    }
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
+
+/// See [`AsyncIterator::enumerate`]
+pub struct Enumerate<I> {
+    iter: I,
+    pos: usize,
+}
+
+impl<I> Enumerate<I> {
+    pub(crate) fn new(iter: I) -> Enumerate<I> {
+        Enumerate { iter, pos: 0 }
+    }
+
+    fn project(self: Pin<&mut Self>) -> (Pin<&mut I>, &mut usize) {
+        // SAFETY: `iter` is structurally pinned and never moved out of, `pos` is never pinned
+        unsafe {
+            let this = self.get_unchecked_mut();
+            (Pin::new_unchecked(&mut this.iter), &mut this.pos)
+        }
+    }
+}
+
+impl<I> AsyncIterator for Enumerate<I>
+where
+    I: AsyncIterator,
+{
+    type Item<'a> = (usize, I::Item<'a>)
+    where
+        Self: 'a;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item<'_>>> {
+        let (iter, pos) = self.project();
+        match iter.poll_next(cx) {
+            Poll::Ready(Some(val)) => {
+                let out = (*pos, val);
+                *pos += 1;
+                Poll::Ready(Some(out))
+            }
+            Poll::Ready(None) => Poll::Ready(None),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// See [`AsyncIterator::take`]
+pub struct Take<I> {
+    iter: I,
+    take: usize,
+}
+
+impl<I> Take<I> {
+    pub(crate) fn new(iter: I, take: usize) -> Take<I> {
+        Take { iter, take }
+    }
+
+    fn project(self: Pin<&mut Self>) -> (Pin<&mut I>, &mut usize) {
+        // SAFETY: `iter` is structurally pinned and never moved out of, `take` is never pinned
+        unsafe {
+            let this = self.get_unchecked_mut();
+            (Pin::new_unchecked(&mut this.iter), &mut this.take)
+        }
+    }
+}
+
+impl<I> AsyncIterator for Take<I>
+where
+    I: AsyncIterator,
+{
+    type Item<'a> = I::Item<'a>
+    where
+        Self: 'a;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item<'_>>> {
+        let (iter, take) = self.project();
+        if *take == 0 {
+            return Poll::Ready(None);
+        }
+        let out = iter.poll_next(cx);
+        if let Poll::Ready(Some(_)) = out {
+            *take -= 1;
+        }
+        out
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.iter.size_hint();
+        let upper = match upper {
+            Some(upper) => upper.min(self.take),
+            None => self.take,
+        };
+        (lower.min(self.take), Some(upper))
+    }
+}
+
+/// See [`AsyncIterator::for_each`]
+pub struct ForEach<I, F> {
+    iter: I,
+    func: F,
+}
+
+impl<I, F> ForEach<I, F> {
+    pub(crate) fn new(iter: I, func: F) -> ForEach<I, F> {
+        ForEach { iter, func }
+    }
+
+    fn project(self: Pin<&mut Self>) -> (Pin<&mut I>, &mut F) {
+        // SAFETY: `iter` is structurally pinned and never moved out of, `func` is never pinned
+        unsafe {
+            let this = self.get_unchecked_mut();
+            (Pin::new_unchecked(&mut this.iter), &mut this.func)
+        }
+    }
+}
+
+impl<I, F> Future for ForEach<I, F>
+where
+    I: AsyncIterator,
+    F: FnMut(I::Item<'_>),
+{
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let (mut iter, func) = self.project();
+        loop {
+            match iter.as_mut().poll_next(cx) {
+                Poll::Ready(Some(val)) => func(val),
+                Poll::Ready(None) => return Poll::Ready(()),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use super::*;
+use crate::iter::IntoLending;
+use core::future::Future;
+use core::task::Waker;
+
+fn block_on<F: Future>(fut: F) -> F::Output {
+    let mut fut = core::pin::pin!(fut);
+    let mut cx = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(out) = fut.as_mut().poll(&mut cx) {
+            return out;
+        }
+    }
+}
+
+/// Lends its internal buffer, refilled with the next value after every other poll
+struct Reader {
+    buf: [u8; 4],
+    next: u8,
+    end: u8,
+    ready: bool,
+}
+
+impl Reader {
+    fn new(end: u8) -> Reader {
+        Reader {
+            buf: [0; 4],
+            next: 0,
+            end,
+            ready: false,
+        }
+    }
+}
+
+impl AsyncIterator for Reader {
+    type Item<'a> = &'a mut [u8];
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item<'_>>> {
+        let this = self.get_mut();
+        if !this.ready {
+            this.ready = true;
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        this.ready = false;
+        if this.next == this.end {
+            return Poll::Ready(None);
+        }
+        this.buf = [this.next; 4];
+        this.next += 1;
+        Poll::Ready(Some(&mut this.buf))
+    }
+}
+
+#[test]
+fn async_next() {
+    let mut reader = Reader::new(2);
+    block_on(async {
+        assert_eq!(reader.next().await, Some(&mut [0; 4][..]));
+        let buf = reader.next().await.unwrap();
+        buf[0] = 5;
+        assert_eq!(buf, &[5, 1, 1, 1]);
+        assert_eq!(reader.next().await, None);
+    });
+}
+
+#[test]
+fn async_adapters() {
+    let mut out = [0; 3];
+    let fut = Reader::new(10)
+        .touch(|buf| buf[1] *= 2)
+        .filter(|buf| buf[0] % 3 == 0)
+        .enumerate()
+        .take(3)
+        .for_each(|(idx, buf)| out[idx] = buf[1]);
+    block_on(fut);
+
+    assert_eq!(out, [0, 6, 12]);
+}
+
+#[test]
+fn async_from_iter() {
+    let mut iter = [1, 2, 3].into_iter().into_lending().into_async();
+    let mut sum = 0;
+    block_on(async {
+        while let Some(val) = iter.next().await {
+            sum += val;
+        }
+    });
+    assert_eq!(sum, 6);
+}
@@ -0,0 +1,385 @@
+//! GAT equivalent of `std` borrowing traits
+
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+
+use crate::ops::Deref;
+
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, string::String, vec::Vec};
+
+/// A trait for borrowing data. As opposed to `std`, the borrowed form can be a non-reference.
+/// This allows things like borrowing a `(String, String)` as a `(&str, &str)`, so it can be
+/// looked up in a map without allocating.
+///
+/// Like [`core::borrow::Borrow`], the borrowed form is expected to behave identically to the
+/// owned value - see [`BorrowEq`], [`BorrowOrd`] and [`BorrowHash`] for comparing values by
+/// their borrowed form.
+pub trait Borrow {
+    /// The borrowed form of this value
+    type Borrowed<'a>: Copy
+    where
+        Self: 'a;
+
+    /// Immutably borrow this value
+    fn borrow(&self) -> Self::Borrowed<'_>;
+
+    /// Shorten the lifetime of a borrowed value. Borrowed forms are expected to be covariant, so
+    /// this is generally implemented as the identity function.
+    fn shorten<'a: 'b, 'b>(val: Self::Borrowed<'a>) -> Self::Borrowed<'b>
+    where
+        Self: 'a;
+}
+
+/// A generalization of [`Clone`] to views. As opposed to `std`, this is implemented on the view
+/// type itself, such as `&str` or a custom `MatrixRef<'a>`, and the view doesn't need to be a
+/// reference.
+///
+/// The owned form is expected to borrow back into the view type, so
+/// `<Self::Owned as Borrow>::Borrowed<'a>` should be `Self` for the appropriate lifetime.
+pub trait ToOwned {
+    /// The owned form of this view
+    type Owned: Borrow;
+
+    /// Create owned data from this view, usually by cloning
+    fn to_owned(&self) -> Self::Owned;
+}
+
+/// A clone-on-write smart pointer over a view type. Like `std`'s `Cow`, but holding a
+/// [`Borrow::Borrowed`] view instead of a reference, so `B` is the owned type rather than the
+/// borrowed one.
+///
+/// This implements [`ops::Deref`](crate::ops::Deref), yielding the view regardless of the variant.
+pub enum GatCow<'a, B: Borrow + 'a> {
+    /// Borrowed data
+    Borrowed(B::Borrowed<'a>),
+    /// Owned data
+    Owned(B),
+}
+
+impl<'a, B: Borrow + 'a> GatCow<'a, B> {
+    /// Check whether this contains borrowed data
+    pub fn is_borrowed(&self) -> bool {
+        matches!(self, GatCow::Borrowed(_))
+    }
+
+    /// Check whether this contains owned data
+    pub fn is_owned(&self) -> bool {
+        matches!(self, GatCow::Owned(_))
+    }
+
+    /// Get a mutable reference to the owned data, converting the borrowed data into owned data
+    /// first if necessary
+    pub fn to_mut(&mut self) -> &mut B
+    where
+        B::Borrowed<'a>: ToOwned<Owned = B>,
+    {
+        if let GatCow::Borrowed(val) = *self {
+            *self = GatCow::Owned(val.to_owned());
+        }
+        match self {
+            GatCow::Borrowed(_) => unreachable!(),
+            GatCow::Owned(val) => val,
+        }
+    }
+
+    /// Extract the owned data, converting the borrowed data into owned data if necessary
+    pub fn into_owned(self) -> B
+    where
+        B::Borrowed<'a>: ToOwned<Owned = B>,
+    {
+        match self {
+            GatCow::Borrowed(val) => val.to_owned(),
+            GatCow::Owned(val) => val,
+        }
+    }
+}
+
+impl<'a, B: Borrow + 'a> Deref for GatCow<'a, B> {
+    type Target<'b> = B::Borrowed<'b>
+    where
+        Self: 'b;
+
+    fn deref(&self) -> Self::Target<'_> {
+        match self {
+            GatCow::Borrowed(val) => B::shorten(*val),
+            GatCow::Owned(val) => val.borrow(),
+        }
+    }
+}
+
+impl<'a, B: Borrow + Clone + 'a> Clone for GatCow<'a, B> {
+    fn clone(&self) -> Self {
+        match self {
+            GatCow::Borrowed(val) => GatCow::Borrowed(*val),
+            GatCow::Owned(val) => GatCow::Owned(val.clone()),
+        }
+    }
+}
+
+impl<'a, B> fmt::Debug for GatCow<'a, B>
+where
+    B: Borrow + fmt::Debug + 'a,
+    B::Borrowed<'a>: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GatCow::Borrowed(val) => val.fmt(f),
+            GatCow::Owned(val) => val.fmt(f),
+        }
+    }
+}
+
+/// Equality of values by their borrowed form
+///
+/// This is implemented for any [`Borrow`] type whose borrowed form is [`Eq`] for every lifetime.
+/// As [`Borrow::Borrowed`] requires `Self: 'a`, the compiler can currently only prove that for
+/// `'static` types, so this isn't implemented for types holding borrows, such as `&'a str` or
+/// `Vec<&'a str>` with a non-`'static` lifetime. The same goes for [`BorrowOrd`] and
+/// [`BorrowHash`].
+pub trait BorrowEq: Borrow {
+    /// Check whether two borrowed values are equal
+    fn eq_borrowed<'a>(left: Self::Borrowed<'a>, right: Self::Borrowed<'a>) -> bool;
+}
+
+impl<T> BorrowEq for T
+where
+    T: ?Sized + Borrow + 'static,
+    for<'a> T::Borrowed<'a>: Eq,
+{
+    fn eq_borrowed<'a>(left: Self::Borrowed<'a>, right: Self::Borrowed<'a>) -> bool {
+        left == right
+    }
+}
+
+/// Ordering of values by their borrowed form. Like [`BorrowEq`], this is only implemented for
+/// `'static` types.
+pub trait BorrowOrd: BorrowEq {
+    /// Compare two borrowed values
+    fn cmp_borrowed<'a>(left: Self::Borrowed<'a>, right: Self::Borrowed<'a>) -> Ordering;
+}
+
+impl<T> BorrowOrd for T
+where
+    T: ?Sized + Borrow + 'static,
+    for<'a> T::Borrowed<'a>: Ord,
+{
+    fn cmp_borrowed<'a>(left: Self::Borrowed<'a>, right: Self::Borrowed<'a>) -> Ordering {
+        left.cmp(&right)
+    }
+}
+
+/// Hashing of values by their borrowed form. Like [`BorrowEq`], this is only implemented for
+/// `'static` types.
+pub trait BorrowHash: BorrowEq {
+    /// Hash a borrowed value
+    fn hash_borrowed<H: Hasher>(val: Self::Borrowed<'_>, state: &mut H);
+}
+
+impl<T> BorrowHash for T
+where
+    T: ?Sized + Borrow + 'static,
+    for<'a> T::Borrowed<'a>: Eq + Hash,
+{
+    fn hash_borrowed<H: Hasher>(val: Self::Borrowed<'_>, state: &mut H) {
+        val.hash(state)
+    }
+}
+
+impl<T: ?Sized + Borrow> Borrow for &T {
+    type Borrowed<'a> = T::Borrowed<'a>
+    where
+        Self: 'a;
+
+    fn borrow(&self) -> Self::Borrowed<'_> {
+        T::borrow(self)
+    }
+
+    fn shorten<'a: 'b, 'b>(val: Self::Borrowed<'a>) -> Self::Borrowed<'b>
+    where
+        Self: 'a,
+    {
+        T::shorten(val)
+    }
+}
+
+impl Borrow for str {
+    type Borrowed<'a> = &'a str;
+
+    fn borrow(&self) -> Self::Borrowed<'_> {
+        self
+    }
+
+    fn shorten<'a: 'b, 'b>(val: Self::Borrowed<'a>) -> Self::Borrowed<'b> {
+        val
+    }
+}
+
+impl<T> Borrow for [T] {
+    type Borrowed<'a> = &'a [T]
+    where
+        Self: 'a;
+
+    fn borrow(&self) -> Self::Borrowed<'_> {
+        self
+    }
+
+    fn shorten<'a: 'b, 'b>(val: Self::Borrowed<'a>) -> Self::Borrowed<'b>
+    where
+        Self: 'a,
+    {
+        val
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Borrow for String {
+    type Borrowed<'a> = &'a str;
+
+    fn borrow(&self) -> Self::Borrowed<'_> {
+        self
+    }
+
+    fn shorten<'a: 'b, 'b>(val: Self::Borrowed<'a>) -> Self::Borrowed<'b> {
+        val
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> Borrow for Vec<T> {
+    type Borrowed<'a> = &'a [T]
+    where
+        Self: 'a;
+
+    fn borrow(&self) -> Self::Borrowed<'_> {
+        self
+    }
+
+    fn shorten<'a: 'b, 'b>(val: Self::Borrowed<'a>) -> Self::Borrowed<'b>
+    where
+        Self: 'a,
+    {
+        val
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: ?Sized + Borrow> Borrow for Box<T> {
+    type Borrowed<'a> = T::Borrowed<'a>
+    where
+        Self: 'a;
+
+    fn borrow(&self) -> Self::Borrowed<'_> {
+        T::borrow(self)
+    }
+
+    fn shorten<'a: 'b, 'b>(val: Self::Borrowed<'a>) -> Self::Borrowed<'b>
+    where
+        Self: 'a,
+    {
+        T::shorten(val)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> ToOwned for &T
+where
+    T: ?Sized + alloc::borrow::ToOwned,
+    T::Owned: Borrow,
+{
+    type Owned = T::Owned;
+
+    fn to_owned(&self) -> Self::Owned {
+        T::to_owned(self)
+    }
+}
+
+macro_rules! impl_copy {
+    ($($ty:ty),* $(,)?) => {
+        $(
+        impl ToOwned for $ty {
+            type Owned = $ty;
+
+            fn to_owned(&self) -> Self::Owned {
+                *self
+            }
+        }
+
+        impl Borrow for $ty {
+            type Borrowed<'a> = $ty;
+
+            fn borrow(&self) -> Self::Borrowed<'_> {
+                *self
+            }
+
+            fn shorten<'a: 'b, 'b>(val: Self::Borrowed<'a>) -> Self::Borrowed<'b> {
+                val
+            }
+        }
+        )*
+    };
+}
+
+impl_copy!(
+    (),
+    bool,
+    char,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize
+);
+
+macro_rules! impl_tuple {
+    ($($name:ident),+) => {
+        impl<$($name: Borrow),+> Borrow for ($($name,)+) {
+            type Borrowed<'a> = ($($name::Borrowed<'a>,)+)
+            where
+                Self: 'a;
+
+            #[allow(non_snake_case)]
+            fn borrow(&self) -> Self::Borrowed<'_> {
+                let ($($name,)+) = self;
+                ($($name.borrow(),)+)
+            }
+
+            #[allow(non_snake_case)]
+            fn shorten<'a: 'b, 'b>(val: Self::Borrowed<'a>) -> Self::Borrowed<'b>
+            where
+                Self: 'a,
+            {
+                let ($($name,)+) = val;
+                ($($name::shorten($name),)+)
+            }
+        }
+
+        impl<$($name: ToOwned),+> ToOwned for ($($name,)+) {
+            type Owned = ($($name::Owned,)+);
+
+            #[allow(non_snake_case)]
+            fn to_owned(&self) -> Self::Owned {
+                let ($($name,)+) = self;
+                ($($name.to_owned(),)+)
+            }
+        }
+    };
+}
+
+impl_tuple!(A);
+impl_tuple!(A, B);
+impl_tuple!(A, B, C);
+impl_tuple!(A, B, C, D);
+impl_tuple!(A, B, C, D, E);
+impl_tuple!(A, B, C, D, E, F);
+
+#[cfg(all(test, feature = "alloc"))]
+mod test;
@@ -0,0 +1,75 @@
+use super::*;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+#[derive(Debug, PartialEq)]
+struct Matrix {
+    data: Vec<u32>,
+    width: usize,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct MatrixRef<'a> {
+    data: &'a [u32],
+    width: usize,
+}
+
+impl MatrixRef<'_> {
+    fn row(&self, idx: usize) -> &[u32] {
+        &self.data[idx * self.width..(idx + 1) * self.width]
+    }
+}
+
+impl Borrow for Matrix {
+    type Borrowed<'a> = MatrixRef<'a>;
+
+    fn borrow(&self) -> Self::Borrowed<'_> {
+        MatrixRef {
+            data: &self.data,
+            width: self.width,
+        }
+    }
+
+    fn shorten<'a: 'b, 'b>(val: Self::Borrowed<'a>) -> Self::Borrowed<'b> {
+        val
+    }
+}
+
+impl ToOwned for MatrixRef<'_> {
+    type Owned = Matrix;
+
+    fn to_owned(&self) -> Self::Owned {
+        Matrix {
+            data: self.data.to_vec(),
+            width: self.width,
+        }
+    }
+}
+
+#[test]
+fn borrow_tuple() {
+    let key = ("a".to_string(), 1u8, Vec::from([1, 2]));
+    assert_eq!(key.borrow(), ("a", 1, &[1, 2][..]));
+    assert!(<(String, u8)>::eq_borrowed(("a", 1), ("a", 1)));
+    assert_eq!(("a", 1u8).to_owned(), ("a".to_string(), 1));
+}
+
+#[test]
+fn cow_matrix() {
+    let owned = Matrix {
+        data: Vec::from([1, 2, 3, 4]),
+        width: 2,
+    };
+
+    let mut cow = GatCow::<Matrix>::Borrowed(owned.borrow());
+    assert!(cow.is_borrowed());
+    assert_eq!(cow.deref().row(1), &[3, 4]);
+
+    cow.to_mut().data[0] = 5;
+    assert!(cow.is_owned());
+    assert_eq!(cow.deref().row(0), &[5, 2]);
+    assert_eq!(owned.data[0], 1);
+
+    let cow = GatCow::<Matrix>::Borrowed(owned.borrow());
+    assert_eq!(cow.into_owned(), owned);
+}
@@ -0,0 +1,25 @@
+//! Collection types and traits which take advantage of the GAT traits in this crate, such as
+//! containers whose indexing operations return proxy values instead of plain references
+
+#[cfg(feature = "alloc")]
+mod bitvec;
+mod deque;
+#[cfg(feature = "alloc")]
+mod edit;
+#[cfg(feature = "alloc")]
+mod map;
+mod ring;
+mod traits;
+
+#[cfg(feature = "alloc")]
+pub use bitvec::*;
+pub use deque::*;
+#[cfg(feature = "alloc")]
+pub use edit::*;
+#[cfg(feature = "alloc")]
+pub use map::*;
+pub use ring::*;
+pub use traits::*;
+
+#[cfg(test)]
+mod test;
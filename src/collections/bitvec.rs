@@ -0,0 +1,291 @@
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::{Deref, DerefMut};
+
+use crate::iter::Iterator;
+use crate::ops::{Index, IndexMut};
+
+const BITS: usize = usize::BITS as usize;
+
+fn split(idx: usize) -> (usize, usize) {
+    (idx / BITS, 1 << (idx % BITS))
+}
+
+/// A growable vector of bits, packed into words. Indexing yields a `bool` by value, and
+/// mutable indexing yields a [`BitMut`] proxy - something [`core::ops::IndexMut`] can't express.
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct BitVec {
+    words: Vec<usize>,
+    len: usize,
+}
+
+impl BitVec {
+    /// Create a new, empty bit vector
+    pub fn new() -> BitVec {
+        BitVec {
+            words: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Create a new bit vector of `len` bits, all set to `val`
+    pub fn from_elem(val: bool, len: usize) -> BitVec {
+        let fill = if val { usize::MAX } else { 0 };
+        let mut out = BitVec {
+            words: alloc::vec![fill; len.div_ceil(BITS)],
+            len,
+        };
+        out.clear_unused();
+        out
+    }
+
+    /// Get the number of bits in this vector
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check whether this vector contains no bits
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Add a bit to the end of this vector
+    pub fn push(&mut self, val: bool) {
+        if self.len.is_multiple_of(BITS) {
+            self.words.push(0);
+        }
+        self.len += 1;
+        self.set(self.len - 1, val);
+    }
+
+    /// Remove the last bit of this vector, returning it, or `None` if the vector is empty
+    pub fn pop(&mut self) -> Option<bool> {
+        let out = self.get(self.len.checked_sub(1)?)?;
+        self.set(self.len - 1, false);
+        self.len -= 1;
+        if self.len.is_multiple_of(BITS) {
+            self.words.pop();
+        }
+        Some(out)
+    }
+
+    /// Get the bit at `idx`, or `None` if it is out of bounds
+    pub fn get(&self, idx: usize) -> Option<bool> {
+        if idx >= self.len {
+            return None;
+        }
+        let (word, mask) = split(idx);
+        Some(self.words[word] & mask != 0)
+    }
+
+    /// Get a mutable proxy to the bit at `idx`, or `None` if it is out of bounds
+    pub fn get_mut(&mut self, idx: usize) -> Option<BitMut<'_>> {
+        if idx >= self.len {
+            return None;
+        }
+        let (word, mask) = split(idx);
+        Some(BitMut::new(&mut self.words[word], mask))
+    }
+
+    /// Set the bit at `idx` to `val`
+    ///
+    /// # Panics
+    ///
+    /// If `idx` is out of bounds
+    pub fn set(&mut self, idx: usize, val: bool) {
+        self.index_mut(idx).set(val);
+    }
+
+    /// Flip the bit at `idx`
+    ///
+    /// # Panics
+    ///
+    /// If `idx` is out of bounds
+    pub fn toggle(&mut self, idx: usize) {
+        self.index_mut(idx).toggle();
+    }
+
+    /// Get an iterator over the bits of this vector
+    pub fn iter(&self) -> BitIter<'_> {
+        BitIter { bits: self, pos: 0 }
+    }
+
+    /// Get a lending iterator over mutable proxies to each bit of this vector
+    pub fn iter_mut(&mut self) -> BitIterMut<'_> {
+        BitIterMut {
+            words: &mut self.words,
+            pos: 0,
+            len: self.len,
+        }
+    }
+
+    fn clear_unused(&mut self) {
+        if !self.len.is_multiple_of(BITS) {
+            if let Some(last) = self.words.last_mut() {
+                *last &= (1 << (self.len % BITS)) - 1;
+            }
+        }
+    }
+}
+
+impl fmt::Debug for BitVec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl core::iter::FromIterator<bool> for BitVec {
+    fn from_iter<T: core::iter::IntoIterator<Item = bool>>(iter: T) -> Self {
+        let mut out = BitVec::new();
+        out.extend(iter);
+        out
+    }
+}
+
+impl Extend<bool> for BitVec {
+    fn extend<T: core::iter::IntoIterator<Item = bool>>(&mut self, iter: T) {
+        for val in iter {
+            self.push(val);
+        }
+    }
+}
+
+impl Index<usize> for BitVec {
+    type Output<'a> = bool;
+
+    fn index(&self, idx: usize) -> Self::Output<'_> {
+        match self.get(idx) {
+            Some(val) => val,
+            None => panic!(
+                "index out of bounds: the len is {} but the index is {}",
+                self.len, idx
+            ),
+        }
+    }
+}
+
+impl IndexMut<usize> for BitVec {
+    type OutputMut<'a> = BitMut<'a>;
+
+    fn index_mut(&mut self, idx: usize) -> Self::OutputMut<'_> {
+        let len = self.len;
+        match self.get_mut(idx) {
+            Some(val) => val,
+            None => panic!(
+                "index out of bounds: the len is {} but the index is {}",
+                len, idx
+            ),
+        }
+    }
+}
+
+/// A mutable proxy to a single bit. Changes made through this proxy are written back to the
+/// underlying storage when it is dropped.
+///
+/// Dereferences to a `bool`, so `*bits.index_mut(0) = true` works the same as with a normal
+/// mutable reference.
+pub struct BitMut<'a> {
+    word: &'a mut usize,
+    mask: usize,
+    val: bool,
+}
+
+impl<'a> BitMut<'a> {
+    fn new(word: &'a mut usize, mask: usize) -> BitMut<'a> {
+        let val = *word & mask != 0;
+        BitMut { word, mask, val }
+    }
+
+    /// Get the current value of this bit
+    pub fn get(&self) -> bool {
+        self.val
+    }
+
+    /// Set the value of this bit
+    pub fn set(&mut self, val: bool) {
+        self.val = val;
+    }
+
+    /// Flip the value of this bit
+    pub fn toggle(&mut self) {
+        self.val = !self.val;
+    }
+}
+
+impl Deref for BitMut<'_> {
+    type Target = bool;
+
+    fn deref(&self) -> &Self::Target {
+        &self.val
+    }
+}
+
+impl DerefMut for BitMut<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.val
+    }
+}
+
+impl Drop for BitMut<'_> {
+    fn drop(&mut self) {
+        if self.val {
+            *self.word |= self.mask;
+        } else {
+            *self.word &= !self.mask;
+        }
+    }
+}
+
+impl fmt::Debug for BitMut<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.val.fmt(f)
+    }
+}
+
+/// See [`BitVec::iter`]
+pub struct BitIter<'a> {
+    bits: &'a BitVec,
+    pos: usize,
+}
+
+impl core::iter::Iterator for BitIter<'_> {
+    type Item = bool;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let out = self.bits.get(self.pos)?;
+        self.pos += 1;
+        Some(out)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.bits.len - self.pos;
+        (len, Some(len))
+    }
+}
+
+/// See [`BitVec::iter_mut`]
+pub struct BitIterMut<'a> {
+    words: &'a mut [usize],
+    pos: usize,
+    len: usize,
+}
+
+impl Iterator for BitIterMut<'_> {
+    type Item<'a> = BitMut<'a>
+    where
+        Self: 'a;
+
+    fn next(&mut self) -> Option<Self::Item<'_>> {
+        if self.pos >= self.len {
+            return None;
+        }
+        let (word, mask) = split(self.pos);
+        self.pos += 1;
+        Some(BitMut::new(&mut self.words[word], mask))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len - self.pos;
+        (len, Some(len))
+    }
+}
@@ -0,0 +1,515 @@
+use core::fmt;
+use core::ops::{
+    Bound, Range, RangeBounds, RangeFrom, RangeFull, RangeInclusive, RangeTo, RangeToInclusive,
+};
+
+use super::RingBuffer;
+use crate::convert::{AsView, AsViewMut, IntoView};
+use crate::iter::{FromCore, IntoLending};
+use crate::ops::{Index, IndexMut};
+
+#[cfg(feature = "alloc")]
+use alloc::collections::VecDeque;
+
+/// Convert any range into a concrete `start..end` range, checking it against `len`
+///
+/// # Panics
+///
+/// If the range is out of bounds or decreasing, or an inclusive bound is `usize::MAX`
+fn to_range<R: RangeBounds<usize>>(range: R, len: usize) -> Range<usize> {
+    let start = match range.start_bound() {
+        Bound::Included(&s) => s,
+        Bound::Excluded(&s) => s
+            .checked_add(1)
+            .expect("attempted to index deque from after maximum usize"),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&e) => e
+            .checked_add(1)
+            .expect("attempted to index deque up to maximum usize"),
+        Bound::Excluded(&e) => e,
+        Bound::Unbounded => len,
+    };
+    assert!(
+        start <= end,
+        "range start index {} should be <= range end index {}",
+        start,
+        end
+    );
+    assert!(
+        end <= len,
+        "range end index {} out of range for deque of length {}",
+        end,
+        len
+    );
+    start..end
+}
+
+/// Split a range over two concatenated slices into a range over each slice
+fn split_range(front_len: usize, range: Range<usize>) -> (Range<usize>, Range<usize>) {
+    if range.end <= front_len {
+        (range, 0..0)
+    } else if range.start >= front_len {
+        (0..0, range.start - front_len..range.end - front_len)
+    } else {
+        (range.start..front_len, 0..range.end - front_len)
+    }
+}
+
+/// An immutable view of a possibly non-contiguous range of a double-ended queue, made of two
+/// slices. This is the output of indexing a [`RingBuffer`](super::RingBuffer) with a range,
+/// something [`core::ops::Index`] can't express as the range may wrap around.
+///
+/// Under [`gatify`](crate::gatify), it's also the output of indexing a `VecDeque` with a range.
+///
+/// This and [`DequeRangeMut`] iterate over their items with `iter` and `iter_mut`, and convert
+/// into lending iterators through [`IntoIterator`](crate::iter::IntoIterator).
+pub struct DequeRange<'a, T> {
+    front: &'a [T],
+    back: &'a [T],
+}
+
+impl<'a, T> DequeRange<'a, T> {
+    /// Create a new view from the two halves of a deque, in order
+    pub fn from_slices(front: &'a [T], back: &'a [T]) -> DequeRange<'a, T> {
+        DequeRange { front, back }
+    }
+
+    /// Get the number of items in this view
+    pub fn len(&self) -> usize {
+        self.front.len() + self.back.len()
+    }
+
+    /// Check whether this view contains no items
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the two slices making up this view, in order
+    pub fn as_slices(&self) -> (&'a [T], &'a [T]) {
+        (self.front, self.back)
+    }
+
+    /// Get the item at `idx`, or `None` if it is out of bounds
+    pub fn get(&self, idx: usize) -> Option<&'a T> {
+        match idx.checked_sub(self.front.len()) {
+            Some(idx) => self.back.get(idx),
+            None => self.front.get(idx),
+        }
+    }
+
+    /// Get a sub-view of this view
+    ///
+    /// # Panics
+    ///
+    /// If the range is out of bounds
+    pub fn range<R: RangeBounds<usize>>(&self, range: R) -> DequeRange<'a, T> {
+        let (front, back) = split_range(self.front.len(), to_range(range, self.len()));
+        DequeRange {
+            front: &self.front[front],
+            back: &self.back[back],
+        }
+    }
+
+    /// Get an iterator over the items of this view
+    pub fn iter(&self) -> DequeIter<'a, T> {
+        DequeIter {
+            front: self.front.iter(),
+            back: self.back.iter(),
+        }
+    }
+}
+
+impl<T> Clone for DequeRange<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for DequeRange<'_, T> {}
+
+impl<T: fmt::Debug> fmt::Debug for DequeRange<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for DequeRange<'_, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<T> core::ops::Index<usize> for DequeRange<'_, T> {
+    type Output = T;
+
+    fn index(&self, idx: usize) -> &Self::Output {
+        self.get(idx).expect("Out of bounds access")
+    }
+}
+
+impl<'a, T> crate::iter::IntoIterator for DequeRange<'a, T> {
+    type IntoIter = FromCore<DequeIter<'a, T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter().into_lending()
+    }
+}
+
+impl<'a, T> core::iter::IntoIterator for DequeRange<'a, T> {
+    type Item = &'a T;
+    type IntoIter = DequeIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T> From<&'a VecDeque<T>> for DequeRange<'a, T> {
+    fn from(deque: &'a VecDeque<T>) -> Self {
+        let (front, back) = deque.as_slices();
+        DequeRange { front, back }
+    }
+}
+
+/// A mutable view of a possibly non-contiguous range of a double-ended queue, made of two
+/// slices. This is the output of mutably indexing a [`RingBuffer`](super::RingBuffer) with a
+/// range.
+pub struct DequeRangeMut<'a, T> {
+    front: &'a mut [T],
+    back: &'a mut [T],
+}
+
+impl<'a, T> DequeRangeMut<'a, T> {
+    /// Create a new view from the two halves of a deque, in order
+    pub fn from_slices(front: &'a mut [T], back: &'a mut [T]) -> DequeRangeMut<'a, T> {
+        DequeRangeMut { front, back }
+    }
+
+    /// Get the number of items in this view
+    pub fn len(&self) -> usize {
+        self.front.len() + self.back.len()
+    }
+
+    /// Check whether this view contains no items
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the two slices making up this view, in order
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        (self.front, self.back)
+    }
+
+    /// Get the two slices making up this view mutably, in order
+    pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        (self.front, self.back)
+    }
+
+    /// Get the item at `idx`, or `None` if it is out of bounds
+    pub fn get(&self, idx: usize) -> Option<&T> {
+        match idx.checked_sub(self.front.len()) {
+            Some(idx) => self.back.get(idx),
+            None => self.front.get(idx),
+        }
+    }
+
+    /// Get the item at `idx` mutably, or `None` if it is out of bounds
+    pub fn get_mut(&mut self, idx: usize) -> Option<&mut T> {
+        match idx.checked_sub(self.front.len()) {
+            Some(idx) => self.back.get_mut(idx),
+            None => self.front.get_mut(idx),
+        }
+    }
+
+    /// Get an immutable sub-view of this view
+    ///
+    /// # Panics
+    ///
+    /// If the range is out of bounds
+    pub fn range<R: RangeBounds<usize>>(&self, range: R) -> DequeRange<'_, T> {
+        self.as_range().range(range)
+    }
+
+    /// Get a mutable sub-view of this view
+    ///
+    /// # Panics
+    ///
+    /// If the range is out of bounds
+    pub fn range_mut<R: RangeBounds<usize>>(&mut self, range: R) -> DequeRangeMut<'_, T> {
+        DequeRangeMut {
+            front: self.front,
+            back: self.back,
+        }
+        .into_range_mut(range)
+    }
+
+    /// Convert this view into a mutable sub-view of itself
+    ///
+    /// # Panics
+    ///
+    /// If the range is out of bounds
+    pub fn into_range_mut<R: RangeBounds<usize>>(self, range: R) -> DequeRangeMut<'a, T> {
+        let (front, back) = split_range(self.front.len(), to_range(range, self.len()));
+        DequeRangeMut {
+            front: &mut self.front[front],
+            back: &mut self.back[back],
+        }
+    }
+
+    /// Reborrow this view immutably
+    pub fn as_range(&self) -> DequeRange<'_, T> {
+        DequeRange {
+            front: self.front,
+            back: self.back,
+        }
+    }
+
+    /// Get an iterator over the items of this view
+    pub fn iter(&self) -> DequeIter<'_, T> {
+        self.as_range().iter()
+    }
+
+    /// Get an iterator over mutable references to the items of this view
+    pub fn iter_mut(&mut self) -> DequeIterMut<'_, T> {
+        DequeIterMut {
+            front: self.front.iter_mut(),
+            back: self.back.iter_mut(),
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for DequeRangeMut<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_range().fmt(f)
+    }
+}
+
+impl<T> core::ops::Index<usize> for DequeRangeMut<'_, T> {
+    type Output = T;
+
+    fn index(&self, idx: usize) -> &Self::Output {
+        self.get(idx).expect("Out of bounds access")
+    }
+}
+
+impl<T> core::ops::IndexMut<usize> for DequeRangeMut<'_, T> {
+    fn index_mut(&mut self, idx: usize) -> &mut Self::Output {
+        self.get_mut(idx).expect("Out of bounds access")
+    }
+}
+
+impl<'a, T> crate::iter::IntoIterator for DequeRangeMut<'a, T> {
+    type IntoIter = FromCore<DequeIterMut<'a, T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        core::iter::IntoIterator::into_iter(self).into_lending()
+    }
+}
+
+impl<'a, T> core::iter::IntoIterator for DequeRangeMut<'a, T> {
+    type Item = &'a mut T;
+    type IntoIter = DequeIterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        DequeIterMut {
+            front: self.front.iter_mut(),
+            back: self.back.iter_mut(),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T> From<&'a mut VecDeque<T>> for DequeRangeMut<'a, T> {
+    fn from(deque: &'a mut VecDeque<T>) -> Self {
+        let (front, back) = deque.as_mut_slices();
+        DequeRangeMut { front, back }
+    }
+}
+
+/// See [`DequeRange::iter`]
+pub struct DequeIter<'a, T> {
+    front: core::slice::Iter<'a, T>,
+    back: core::slice::Iter<'a, T>,
+}
+
+impl<'a, T> core::iter::Iterator for DequeIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.front.next().or_else(|| self.back.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.front.len() + self.back.len();
+        (len, Some(len))
+    }
+}
+
+impl<T> DoubleEndedIterator for DequeIter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.back.next_back().or_else(|| self.front.next_back())
+    }
+}
+
+impl<T> ExactSizeIterator for DequeIter<'_, T> {}
+
+/// See [`DequeRangeMut::iter_mut`]
+pub struct DequeIterMut<'a, T> {
+    front: core::slice::IterMut<'a, T>,
+    back: core::slice::IterMut<'a, T>,
+}
+
+impl<'a, T> core::iter::Iterator for DequeIterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.front.next().or_else(|| self.back.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.front.len() + self.back.len();
+        (len, Some(len))
+    }
+}
+
+impl<T> DoubleEndedIterator for DequeIterMut<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.back.next_back().or_else(|| self.front.next_back())
+    }
+}
+
+impl<T> ExactSizeIterator for DequeIterMut<'_, T> {}
+
+macro_rules! impl_range_index {
+    ($($range:ty),* $(,)?) => {
+        $(
+        impl<'a, T> Index<$range> for DequeRange<'a, T> {
+            type Output<'b> = DequeRange<'b, T>
+            where
+                Self: 'b;
+
+            fn index(&self, idx: $range) -> Self::Output<'_> {
+                self.range(idx)
+            }
+        }
+
+        impl<'a, T> Index<$range> for DequeRangeMut<'a, T> {
+            type Output<'b> = DequeRange<'b, T>
+            where
+                Self: 'b;
+
+            fn index(&self, idx: $range) -> Self::Output<'_> {
+                self.range(idx)
+            }
+        }
+
+        impl<'a, T> IndexMut<$range> for DequeRangeMut<'a, T> {
+            type OutputMut<'b> = DequeRangeMut<'b, T>
+            where
+                Self: 'b;
+
+            fn index_mut(&mut self, idx: $range) -> Self::OutputMut<'_> {
+                self.range_mut(idx)
+            }
+        }
+
+        impl<T, const N: usize> Index<$range> for RingBuffer<T, N> {
+            type Output<'b> = DequeRange<'b, T>
+            where
+                Self: 'b;
+
+            fn index(&self, idx: $range) -> Self::Output<'_> {
+                self.range(idx)
+            }
+        }
+
+        impl<T, const N: usize> IndexMut<$range> for RingBuffer<T, N> {
+            type OutputMut<'b> = DequeRangeMut<'b, T>
+            where
+                Self: 'b;
+
+            fn index_mut(&mut self, idx: $range) -> Self::OutputMut<'_> {
+                self.range_mut(idx)
+            }
+        }
+        )*
+    };
+}
+
+impl_range_index!(
+    Range<usize>,
+    RangeFrom<usize>,
+    RangeTo<usize>,
+    RangeInclusive<usize>,
+    RangeToInclusive<usize>,
+    RangeFull,
+);
+
+impl<T> AsView<[T]> for DequeRange<'_, T> {
+    type View<'b> = DequeRange<'b, T>
+    where
+        Self: 'b;
+
+    fn as_view(&self) -> Self::View<'_> {
+        *self
+    }
+}
+
+impl<'a, T> IntoView<[T]> for DequeRange<'a, T> {
+    type View = DequeRange<'a, T>;
+
+    fn into_view(self) -> Self::View {
+        self
+    }
+}
+
+impl<T> AsView<[T]> for DequeRangeMut<'_, T> {
+    type View<'b> = DequeRange<'b, T>
+    where
+        Self: 'b;
+
+    fn as_view(&self) -> Self::View<'_> {
+        self.as_range()
+    }
+}
+
+impl<T> AsViewMut<[T]> for DequeRangeMut<'_, T> {
+    type ViewMut<'b> = DequeRangeMut<'b, T>
+    where
+        Self: 'b;
+
+    fn as_view_mut(&mut self) -> Self::ViewMut<'_> {
+        self.range_mut(..)
+    }
+}
+
+impl<'a, T> IntoView<[T]> for DequeRangeMut<'a, T> {
+    type View = DequeRangeMut<'a, T>;
+
+    fn into_view(self) -> Self::View {
+        self
+    }
+}
+
+impl<T, const N: usize> AsView<[T]> for RingBuffer<T, N> {
+    type View<'b> = DequeRange<'b, T>
+    where
+        Self: 'b;
+
+    fn as_view(&self) -> Self::View<'_> {
+        self.range(..)
+    }
+}
+
+impl<T, const N: usize> AsViewMut<[T]> for RingBuffer<T, N> {
+    type ViewMut<'b> = DequeRangeMut<'b, T>
+    where
+        Self: 'b;
+
+    fn as_view_mut(&mut self) -> Self::ViewMut<'_> {
+        self.range_mut(..)
+    }
+}
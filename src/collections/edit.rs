@@ -0,0 +1,188 @@
+use alloc::vec::Vec;
+use core::ptr;
+
+use crate::iter::Iterator;
+
+/// The positions of an in-progress edit. The buffer is split into the kept elements `..write`,
+/// a gap of moved-out memory `write..read`, and the unvisited elements `read..end`.
+struct Gap {
+    write: usize,
+    read: usize,
+    end: usize,
+}
+
+/// A lending iterator over the elements of a `Vec`, yielding an [`EditEntry`] for each which can
+/// remove, replace, or insert elements around it. Edits are applied in place, with a single pass
+/// of compaction, so a full traversal is amortised O(n) no matter how many edits are made.
+///
+/// Elements inserted through an entry are never visited by the cursor. While the cursor is alive,
+/// the vector appears empty - leaking the cursor leaks the elements, but is otherwise safe.
+pub struct EditCursor<'a, T> {
+    vec: &'a mut Vec<T>,
+    gap: Gap,
+}
+
+impl<'a, T> EditCursor<'a, T> {
+    /// Create a new cursor at the start of `vec`
+    pub fn new(vec: &'a mut Vec<T>) -> EditCursor<'a, T> {
+        let end = vec.len();
+        // SAFETY: Length zero is always valid, the elements are tracked by `gap` from here on
+        unsafe { vec.set_len(0) };
+        EditCursor {
+            vec,
+            gap: Gap {
+                write: 0,
+                read: 0,
+                end,
+            },
+        }
+    }
+}
+
+impl<T> Drop for EditCursor<'_, T> {
+    fn drop(&mut self) {
+        let Gap { write, read, end } = self.gap;
+        let base = self.vec.as_mut_ptr();
+        // SAFETY: `read..end` are initialized elements, and `write <= read`, so moving them down
+        //         leaves `..write + end - read` initialized
+        unsafe {
+            ptr::copy(base.add(read), base.add(write), end - read);
+            self.vec.set_len(write + end - read);
+        }
+    }
+}
+
+impl<T> Iterator for EditCursor<'_, T> {
+    type Item<'a> = EditEntry<'a, T>
+    where
+        Self: 'a;
+
+    fn next(&mut self) -> Option<Self::Item<'_>> {
+        let gap = &mut self.gap;
+        if gap.read == gap.end {
+            return None;
+        }
+        let base = self.vec.as_mut_ptr();
+        // SAFETY: `read` is an initialized element, and `write` is either in the gap or equal to
+        //         `read`, so the element is only moved into memory we own
+        unsafe { ptr::copy(base.add(gap.read), base.add(gap.write), 1) };
+        let cur = gap.write;
+        gap.write += 1;
+        gap.read += 1;
+        Some(EditEntry {
+            vec: self.vec,
+            gap,
+            cur,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.gap.end - self.gap.read;
+        (len, Some(len))
+    }
+}
+
+/// An element lent by an [`EditCursor`]. Elements inserted after this one are placed between it
+/// and the next element of the cursor, in the order they are inserted.
+pub struct EditEntry<'a, T> {
+    vec: &'a mut Vec<T>,
+    gap: &'a mut Gap,
+    cur: usize,
+}
+
+impl<'a, T> EditEntry<'a, T> {
+    /// Make sure there is room for at least one more element before the unvisited ones
+    fn reserve(&mut self) {
+        if self.gap.write < self.gap.read {
+            return;
+        }
+        let Gap { write, read, end } = *self.gap;
+        let tail = end - read;
+        let cap = self
+            .vec
+            .capacity()
+            .saturating_mul(2)
+            .max(write + tail + 1)
+            .max(4);
+        let mut grown = Vec::with_capacity(cap);
+        let new_read = grown.capacity() - tail;
+        let (old, new) = (self.vec.as_mut_ptr(), grown.as_mut_ptr());
+        // SAFETY: `..write` and `read..end` are initialized elements, which are moved to the
+        //         start and end of the new allocation. Both vectors have length zero, so the old
+        //         one only frees its allocation when dropped.
+        unsafe {
+            ptr::copy_nonoverlapping(old, new, write);
+            ptr::copy_nonoverlapping(old.add(read), new.add(new_read), tail);
+        }
+        *self.vec = grown;
+        self.gap.read = new_read;
+        self.gap.end = new_read + tail;
+    }
+
+    /// Get a reference to this element
+    pub fn get(&self) -> &T {
+        // SAFETY: `cur` is always an initialized element
+        unsafe { &*self.vec.as_ptr().add(self.cur) }
+    }
+
+    /// Get a mutable reference to this element
+    pub fn get_mut(&mut self) -> &mut T {
+        // SAFETY: `cur` is always an initialized element
+        unsafe { &mut *self.vec.as_mut_ptr().add(self.cur) }
+    }
+
+    /// Convert into a mutable reference to this element, with the lifetime of the entry
+    pub fn into_mut(self) -> &'a mut T {
+        // SAFETY: `cur` is always an initialized element
+        unsafe { &mut *self.vec.as_mut_ptr().add(self.cur) }
+    }
+
+    /// Replace this element with `val`, returning the old value
+    pub fn replace(&mut self, val: T) -> T {
+        core::mem::replace(self.get_mut(), val)
+    }
+
+    /// Remove this element from the vector, returning it. Any elements inserted after it are
+    /// kept.
+    pub fn remove(self) -> T {
+        let base = self.vec.as_mut_ptr();
+        // SAFETY: `cur` is an initialized element, which is read out then overwritten by shifting
+        //         the elements inserted after it down by one
+        unsafe {
+            let out = ptr::read(base.add(self.cur));
+            ptr::copy(
+                base.add(self.cur + 1),
+                base.add(self.cur),
+                self.gap.write - self.cur - 1,
+            );
+            self.gap.write -= 1;
+            out
+        }
+    }
+
+    /// Insert `val` directly before this element
+    pub fn insert_before(&mut self, val: T) {
+        self.reserve();
+        let base = self.vec.as_mut_ptr();
+        // SAFETY: There is room at `write`, so this element and any inserted after it can be
+        //         shifted up by one, leaving `cur` free to write to
+        unsafe {
+            ptr::copy(
+                base.add(self.cur),
+                base.add(self.cur + 1),
+                self.gap.write - self.cur,
+            );
+            ptr::write(base.add(self.cur), val);
+        }
+        self.cur += 1;
+        self.gap.write += 1;
+    }
+
+    /// Insert `val` after this element and any previously inserted after it
+    pub fn insert_after(&mut self, val: T) {
+        self.reserve();
+        // SAFETY: There is room at `write`, which is past the end of the kept elements
+        unsafe { ptr::write(self.vec.as_mut_ptr().add(self.gap.write), val) };
+        self.gap.write += 1;
+    }
+}
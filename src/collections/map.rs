@@ -0,0 +1,386 @@
+use core::cmp::Ordering;
+use core::fmt;
+#[cfg(feature = "std")]
+use core::hash::{BuildHasher, Hash, Hasher};
+
+use alloc::collections::btree_map;
+#[cfg(feature = "std")]
+use std::collections::hash_map::{self, RandomState};
+
+#[cfg(feature = "std")]
+use crate::borrow::BorrowHash;
+use crate::borrow::{Borrow, BorrowEq, BorrowOrd};
+
+/// A value which can be compared against the keys of a map. Both stored keys and lookups go
+/// through this, so comparisons always happen on the borrowed form.
+trait Query<K: Borrow> {
+    fn borrowed(&self) -> K::Borrowed<'_>;
+}
+
+fn eq_query<'x, K: BorrowEq>(
+    left: &'x (dyn Query<K> + 'x),
+    right: &'x (dyn Query<K> + 'x),
+) -> bool {
+    K::eq_borrowed(left.borrowed(), right.borrowed())
+}
+
+fn cmp_query<'x, K: BorrowOrd>(
+    left: &'x (dyn Query<K> + 'x),
+    right: &'x (dyn Query<K> + 'x),
+) -> Ordering {
+    K::cmp_borrowed(left.borrowed(), right.borrowed())
+}
+
+impl<K: BorrowEq> PartialEq for dyn Query<K> + '_ {
+    fn eq(&self, other: &Self) -> bool {
+        eq_query(self, other)
+    }
+}
+
+impl<K: BorrowEq> Eq for dyn Query<K> + '_ {}
+
+impl<K: BorrowOrd> PartialOrd for dyn Query<K> + '_ {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K: BorrowOrd> Ord for dyn Query<K> + '_ {
+    fn cmp(&self, other: &Self) -> Ordering {
+        cmp_query(self, other)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K: BorrowHash> Hash for dyn Query<K> + '_ {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        K::hash_borrowed(self.borrowed(), state)
+    }
+}
+
+#[derive(Clone)]
+struct Key<K>(K);
+
+impl<K: Borrow> Query<K> for Key<K> {
+    fn borrowed(&self) -> K::Borrowed<'_> {
+        self.0.borrow()
+    }
+}
+
+impl<'a, K: Borrow + 'a> core::borrow::Borrow<dyn Query<K> + 'a> for Key<K> {
+    fn borrow(&self) -> &(dyn Query<K> + 'a) {
+        self
+    }
+}
+
+impl<K: BorrowEq> PartialEq for Key<K> {
+    fn eq(&self, other: &Self) -> bool {
+        eq_query::<K>(self, other)
+    }
+}
+
+impl<K: BorrowEq> Eq for Key<K> {}
+
+impl<K: BorrowOrd> PartialOrd for Key<K> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K: BorrowOrd> Ord for Key<K> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        cmp_query::<K>(self, other)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K: BorrowHash> Hash for Key<K> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (self as &dyn Query<K>).hash(state)
+    }
+}
+
+struct Lookup<'q, K: Borrow + 'q>(K::Borrowed<'q>);
+
+impl<K: Borrow> Query<K> for Lookup<'_, K> {
+    fn borrowed(&self) -> K::Borrowed<'_> {
+        K::shorten(self.0)
+    }
+}
+
+/// An ordered map, like [`alloc::collections::BTreeMap`], whose lookups take the
+/// [`Borrow::Borrowed`] form of the key. This allows looking up a `(String, String)` key with a
+/// `(&str, &str)`.
+///
+/// Keys are ordered by their borrowed form, not their own [`Ord`] impl. This needs [`BorrowOrd`],
+/// so keys must be `'static` - a map can't be keyed by a borrow shorter than that:
+///
+/// ```compile_fail
+/// # use gat_std::collections::BTreeMap;
+/// fn count(words: &[&str]) -> usize {
+///     let mut map = BTreeMap::new();
+///     for word in words {
+///         map.insert(*word, ());
+///     }
+///     map.len()
+/// }
+/// ```
+pub struct BTreeMap<K, V> {
+    inner: btree_map::BTreeMap<Key<K>, V>,
+}
+
+impl<K, V> BTreeMap<K, V> {
+    /// Create a new, empty map
+    pub const fn new() -> BTreeMap<K, V> {
+        BTreeMap {
+            inner: btree_map::BTreeMap::new(),
+        }
+    }
+
+    /// Get the number of entries in this map
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Check whether this map contains no entries
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Remove all entries from this map
+    pub fn clear(&mut self) {
+        self.inner.clear()
+    }
+
+    /// Get an iterator over the entries of this map, ordered by key
+    pub fn iter(&self) -> BTreeMapIter<'_, K, V> {
+        BTreeMapIter(self.inner.iter())
+    }
+}
+
+impl<K: BorrowOrd, V> BTreeMap<K, V> {
+    /// Insert a value into this map, returning the previous value for the key if there was one
+    pub fn insert(&mut self, key: K, val: V) -> Option<V> {
+        self.inner.insert(Key(key), val)
+    }
+
+    /// Get a reference to the value for a key, or `None` if it isn't present
+    pub fn get(&self, key: K::Borrowed<'_>) -> Option<&V> {
+        self.inner.get(&Lookup::<K>(key) as &dyn Query<K>)
+    }
+
+    /// Get a mutable reference to the value for a key, or `None` if it isn't present
+    pub fn get_mut(&mut self, key: K::Borrowed<'_>) -> Option<&mut V> {
+        self.inner.get_mut(&Lookup::<K>(key) as &dyn Query<K>)
+    }
+
+    /// Get the stored key and value for a key, or `None` if it isn't present
+    pub fn get_key_value(&self, key: K::Borrowed<'_>) -> Option<(&K, &V)> {
+        self.inner
+            .get_key_value(&Lookup::<K>(key) as &dyn Query<K>)
+            .map(|(k, v)| (&k.0, v))
+    }
+
+    /// Check whether this map contains a value for a key
+    pub fn contains_key(&self, key: K::Borrowed<'_>) -> bool {
+        self.inner.contains_key(&Lookup::<K>(key) as &dyn Query<K>)
+    }
+
+    /// Remove a key from this map, returning its value if it was present
+    pub fn remove(&mut self, key: K::Borrowed<'_>) -> Option<V> {
+        self.inner.remove(&Lookup::<K>(key) as &dyn Query<K>)
+    }
+}
+
+impl<K, V> Default for BTreeMap<K, V> {
+    fn default() -> Self {
+        BTreeMap::new()
+    }
+}
+
+impl<K: Clone, V: Clone> Clone for BTreeMap<K, V> {
+    fn clone(&self) -> Self {
+        BTreeMap {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for BTreeMap<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K: BorrowOrd, V> FromIterator<(K, V)> for BTreeMap<K, V> {
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        BTreeMap {
+            inner: iter.into_iter().map(|(k, v)| (Key(k), v)).collect(),
+        }
+    }
+}
+
+impl<K: BorrowOrd, V> Extend<(K, V)> for BTreeMap<K, V> {
+    fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
+        self.inner
+            .extend(iter.into_iter().map(|(k, v)| (Key(k), v)))
+    }
+}
+
+/// See [`BTreeMap::iter`]
+pub struct BTreeMapIter<'a, K, V>(btree_map::Iter<'a, Key<K>, V>);
+
+impl<'a, K, V> Iterator for BTreeMapIter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(k, v)| (&k.0, v))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+/// A hash map, like [`std::collections::HashMap`], whose lookups take the
+/// [`Borrow::Borrowed`] form of the key. This allows looking up a `(String, String)` key with a
+/// `(&str, &str)`.
+///
+/// Keys are hashed and compared by their borrowed form, not their own [`Hash`] and [`Eq`] impls.
+/// Like [`BTreeMap`], this needs [`BorrowHash`], so keys must be `'static`.
+#[cfg(feature = "std")]
+pub struct HashMap<K, V, S = RandomState> {
+    inner: hash_map::HashMap<Key<K>, V, S>,
+}
+
+#[cfg(feature = "std")]
+impl<K, V> HashMap<K, V> {
+    /// Create a new, empty map
+    pub fn new() -> HashMap<K, V> {
+        HashMap {
+            inner: hash_map::HashMap::new(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K, V, S> HashMap<K, V, S> {
+    /// Create a new, empty map which will use the given hash builder
+    pub fn with_hasher(hasher: S) -> HashMap<K, V, S> {
+        HashMap {
+            inner: hash_map::HashMap::with_hasher(hasher),
+        }
+    }
+
+    /// Get the number of entries in this map
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Check whether this map contains no entries
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Remove all entries from this map
+    pub fn clear(&mut self) {
+        self.inner.clear()
+    }
+
+    /// Get an iterator over the entries of this map, in arbitrary order
+    pub fn iter(&self) -> HashMapIter<'_, K, V> {
+        HashMapIter(self.inner.iter())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K: BorrowHash, V, S: BuildHasher> HashMap<K, V, S> {
+    /// Insert a value into this map, returning the previous value for the key if there was one
+    pub fn insert(&mut self, key: K, val: V) -> Option<V> {
+        self.inner.insert(Key(key), val)
+    }
+
+    /// Get a reference to the value for a key, or `None` if it isn't present
+    pub fn get(&self, key: K::Borrowed<'_>) -> Option<&V> {
+        self.inner.get(&Lookup::<K>(key) as &dyn Query<K>)
+    }
+
+    /// Get a mutable reference to the value for a key, or `None` if it isn't present
+    pub fn get_mut(&mut self, key: K::Borrowed<'_>) -> Option<&mut V> {
+        self.inner.get_mut(&Lookup::<K>(key) as &dyn Query<K>)
+    }
+
+    /// Get the stored key and value for a key, or `None` if it isn't present
+    pub fn get_key_value(&self, key: K::Borrowed<'_>) -> Option<(&K, &V)> {
+        self.inner
+            .get_key_value(&Lookup::<K>(key) as &dyn Query<K>)
+            .map(|(k, v)| (&k.0, v))
+    }
+
+    /// Check whether this map contains a value for a key
+    pub fn contains_key(&self, key: K::Borrowed<'_>) -> bool {
+        self.inner.contains_key(&Lookup::<K>(key) as &dyn Query<K>)
+    }
+
+    /// Remove a key from this map, returning its value if it was present
+    pub fn remove(&mut self, key: K::Borrowed<'_>) -> Option<V> {
+        self.inner.remove(&Lookup::<K>(key) as &dyn Query<K>)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K, V, S: Default> Default for HashMap<K, V, S> {
+    fn default() -> Self {
+        HashMap::with_hasher(S::default())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K: Clone, V: Clone, S: Clone> Clone for HashMap<K, V, S> {
+    fn clone(&self) -> Self {
+        HashMap {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K: fmt::Debug, V: fmt::Debug, S> fmt::Debug for HashMap<K, V, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K: BorrowHash, V, S: BuildHasher + Default> FromIterator<(K, V)> for HashMap<K, V, S> {
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        HashMap {
+            inner: iter.into_iter().map(|(k, v)| (Key(k), v)).collect(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K: BorrowHash, V, S: BuildHasher> Extend<(K, V)> for HashMap<K, V, S> {
+    fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
+        self.inner
+            .extend(iter.into_iter().map(|(k, v)| (Key(k), v)))
+    }
+}
+
+/// See [`HashMap::iter`]
+#[cfg(feature = "std")]
+pub struct HashMapIter<'a, K, V>(hash_map::Iter<'a, Key<K>, V>);
+
+#[cfg(feature = "std")]
+impl<'a, K, V> Iterator for HashMapIter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(k, v)| (&k.0, v))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
@@ -0,0 +1,238 @@
+use core::fmt;
+use core::mem::MaybeUninit;
+use core::ops::{Range, RangeBounds};
+
+use super::{DequeIter, DequeIterMut, DequeRange, DequeRangeMut};
+
+/// # Safety:
+/// All items in the slice must be initialized
+unsafe fn assume_init<T>(slice: &[MaybeUninit<T>]) -> &[T] {
+    // SAFETY: `MaybeUninit<T>` has the same layout as `T`, and our caller guarantees the items
+    //         are initialized
+    unsafe { core::slice::from_raw_parts(slice.as_ptr().cast::<T>(), slice.len()) }
+}
+
+/// # Safety:
+/// All items in the slice must be initialized
+unsafe fn assume_init_mut<T>(slice: &mut [MaybeUninit<T>]) -> &mut [T] {
+    // SAFETY: `MaybeUninit<T>` has the same layout as `T`, and our caller guarantees the items
+    //         are initialized
+    unsafe { core::slice::from_raw_parts_mut(slice.as_mut_ptr().cast::<T>(), slice.len()) }
+}
+
+/// A fixed-capacity double-ended queue, stored inline without allocation.
+///
+/// As the items may wrap around the end of the storage, indexing this with a range yields a
+/// [`DequeRange`] view instead of a slice.
+pub struct RingBuffer<T, const N: usize> {
+    buf: [MaybeUninit<T>; N],
+    head: usize,
+    len: usize,
+}
+
+impl<T, const N: usize> RingBuffer<T, N> {
+    /// Create a new, empty ring buffer
+    pub const fn new() -> RingBuffer<T, N> {
+        RingBuffer {
+            buf: [const { MaybeUninit::uninit() }; N],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    /// Get the maximum number of items this buffer can hold
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Get the number of items in this buffer
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check whether this buffer contains no items
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Check whether this buffer is at capacity
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    fn phys(&self, idx: usize) -> usize {
+        (self.head + idx) % N
+    }
+
+    fn ranges(&self) -> (Range<usize>, Range<usize>) {
+        let tail = N - self.head;
+        if self.len <= tail {
+            (self.head..self.head + self.len, 0..0)
+        } else {
+            (self.head..N, 0..self.len - tail)
+        }
+    }
+
+    /// Add an item to the back of the buffer, or return it if the buffer is full
+    pub fn push_back(&mut self, val: T) -> Result<(), T> {
+        if self.is_full() {
+            return Err(val);
+        }
+        let idx = self.phys(self.len);
+        self.buf[idx].write(val);
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Add an item to the front of the buffer, or return it if the buffer is full
+    pub fn push_front(&mut self, val: T) -> Result<(), T> {
+        if self.is_full() {
+            return Err(val);
+        }
+        self.head = (self.head + N - 1) % N;
+        self.buf[self.head].write(val);
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Remove an item from the front of the buffer, or return `None` if it is empty
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+        // SAFETY: The head is initialized as the buffer isn't empty, and moving the head past it
+        //         marks it as uninitialized again
+        let out = unsafe { self.buf[self.head].assume_init_read() };
+        self.head = (self.head + 1) % N;
+        self.len -= 1;
+        Some(out)
+    }
+
+    /// Remove an item from the back of the buffer, or return `None` if it is empty
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+        self.len -= 1;
+        let idx = self.phys(self.len);
+        // SAFETY: The item one past the new length was initialized, and decrementing the length
+        //         marks it as uninitialized again
+        Some(unsafe { self.buf[idx].assume_init_read() })
+    }
+
+    /// Get the item at `idx`, or `None` if it is out of bounds
+    pub fn get(&self, idx: usize) -> Option<&T> {
+        if idx >= self.len {
+            return None;
+        }
+        // SAFETY: All items within the length are initialized
+        Some(unsafe { self.buf[self.phys(idx)].assume_init_ref() })
+    }
+
+    /// Get the item at `idx` mutably, or `None` if it is out of bounds
+    pub fn get_mut(&mut self, idx: usize) -> Option<&mut T> {
+        if idx >= self.len {
+            return None;
+        }
+        let idx = self.phys(idx);
+        // SAFETY: All items within the length are initialized
+        Some(unsafe { self.buf[idx].assume_init_mut() })
+    }
+
+    /// Get the items of this buffer as two slices, in order
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let (front, back) = self.ranges();
+        // SAFETY: All items within the length are initialized
+        unsafe { (assume_init(&self.buf[front]), assume_init(&self.buf[back])) }
+    }
+
+    /// Get the items of this buffer mutably as two slices, in order
+    pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        let (front, back) = self.ranges();
+        let (low, high) = self.buf.split_at_mut(self.head);
+        let front = &mut high[front.start - self.head..front.end - self.head];
+        let back = &mut low[back];
+        // SAFETY: All items within the length are initialized
+        unsafe { (assume_init_mut(front), assume_init_mut(back)) }
+    }
+
+    /// Rearrange the storage of this buffer so its items are contiguous, then return them as a
+    /// single mutable slice
+    pub fn make_contiguous(&mut self) -> &mut [T] {
+        if self.head + self.len > N {
+            self.buf.rotate_left(self.head);
+            self.head = 0;
+        }
+        let range = self.head..self.head + self.len;
+        // SAFETY: All items within the length are initialized, and are now contiguous
+        unsafe { assume_init_mut(&mut self.buf[range]) }
+    }
+
+    /// Get an immutable view of a range of this buffer
+    ///
+    /// # Panics
+    ///
+    /// If the range is out of bounds
+    pub fn range<R: RangeBounds<usize>>(&self, range: R) -> DequeRange<'_, T> {
+        let (front, back) = self.as_slices();
+        DequeRange::from_slices(front, back).range(range)
+    }
+
+    /// Get a mutable view of a range of this buffer
+    ///
+    /// # Panics
+    ///
+    /// If the range is out of bounds
+    pub fn range_mut<R: RangeBounds<usize>>(&mut self, range: R) -> DequeRangeMut<'_, T> {
+        let (front, back) = self.as_mut_slices();
+        DequeRangeMut::from_slices(front, back).into_range_mut(range)
+    }
+
+    /// Get an iterator over the items of this buffer
+    pub fn iter(&self) -> DequeIter<'_, T> {
+        self.range(..).iter()
+    }
+
+    /// Get an iterator over mutable references to the items of this buffer
+    pub fn iter_mut(&mut self) -> DequeIterMut<'_, T> {
+        let (front, back) = self.as_mut_slices();
+        core::iter::IntoIterator::into_iter(DequeRangeMut::from_slices(front, back))
+    }
+
+    /// Remove all items from this buffer
+    pub fn clear(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+}
+
+impl<T, const N: usize> Default for RingBuffer<T, N> {
+    fn default() -> Self {
+        RingBuffer::new()
+    }
+}
+
+impl<T, const N: usize> Drop for RingBuffer<T, N> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for RingBuffer<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T, const N: usize> core::ops::Index<usize> for RingBuffer<T, N> {
+    type Output = T;
+
+    fn index(&self, idx: usize) -> &Self::Output {
+        self.get(idx).expect("Out of bounds access")
+    }
+}
+
+impl<T, const N: usize> core::ops::IndexMut<usize> for RingBuffer<T, N> {
+    fn index_mut(&mut self, idx: usize) -> &mut Self::Output {
+        self.get_mut(idx).expect("Out of bounds access")
+    }
+}
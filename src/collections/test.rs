@@ -0,0 +1,284 @@
+use super::*;
+use crate::iter::{IntoIterator, Iterator};
+use crate::ops::{Index, IndexMut};
+
+#[cfg(feature = "alloc")]
+#[test]
+fn bitvec_index() {
+    let mut bits = BitVec::from_elem(false, 70);
+    *bits.index_mut(3) = true;
+    bits.index_mut(65).toggle();
+
+    assert!(bits.index(3));
+    assert!(bits.index(65));
+    assert!(!bits.index(4));
+    assert_eq!(bits.iter().filter(|b| *b).count(), 2);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn bitvec_push_pop() {
+    let mut bits: BitVec = [true, false, true].into_iter().collect();
+    bits.push(true);
+
+    assert_eq!(bits.len(), 4);
+    assert_eq!(bits.pop(), Some(true));
+    assert_eq!(bits.pop(), Some(true));
+    assert_eq!(bits.pop(), Some(false));
+    assert_eq!(bits.pop(), Some(true));
+    assert_eq!(bits.pop(), None);
+    assert_eq!(bits, BitVec::new());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn bitvec_iter_mut() {
+    let mut bits = BitVec::from_elem(false, 10);
+    let mut iter = bits.iter_mut().step_by(2);
+    while let Some(mut b) = iter.next() {
+        b.set(true);
+    }
+
+    let res = bits.iter().enumerate().all(|(idx, b)| b == (idx % 2 == 0));
+    assert!(res);
+}
+
+fn wrapped() -> RingBuffer<u8, 6> {
+    let mut buf = RingBuffer::new();
+    for i in 0..6 {
+        buf.push_back(i).unwrap();
+    }
+    buf.pop_front();
+    buf.pop_front();
+    buf.push_back(6).unwrap();
+    buf.push_back(7).unwrap();
+    buf
+}
+
+#[test]
+fn ring_push_pop() {
+    let mut buf = wrapped();
+
+    assert!(buf.is_full());
+    assert_eq!(buf.push_back(8), Err(8));
+    assert_eq!(buf.as_slices(), (&[2, 3, 4, 5][..], &[6, 7][..]));
+    assert_eq!(buf.pop_back(), Some(7));
+    assert_eq!(buf.pop_front(), Some(2));
+    buf.push_front(1).unwrap();
+    assert!(buf.iter().copied().eq([1, 3, 4, 5, 6]));
+}
+
+#[test]
+fn ring_range_index() {
+    let mut buf = wrapped();
+
+    let range = buf.index(1..5);
+    assert_eq!(range.len(), 4);
+    assert_eq!(range.as_slices(), (&[3, 4, 5][..], &[6][..]));
+    assert_eq!(range.index(2..).as_slices(), (&[5][..], &[6][..]));
+    assert_eq!(*range.index(3), 6);
+
+    let mut range = buf.index_mut(3..);
+    range.index_mut(..2).iter_mut().for_each(|val| *val *= 10);
+    assert!(buf.index(..).iter().copied().eq([2, 3, 4, 50, 60, 7]));
+}
+
+#[test]
+fn ring_range_lending() {
+    let mut buf = wrapped();
+
+    let mut iter = IntoIterator::into_iter(buf.index_mut(1..4));
+    while let Some(val) = iter.next() {
+        *val += 1;
+    }
+    let mut iter = IntoIterator::into_iter(buf.index(..4));
+    assert_eq!(iter.next(), Some(&2));
+    assert_eq!(iter.nth(2), Some(&6));
+    assert_eq!(iter.next(), None);
+}
+
+#[test]
+#[should_panic = "attempted to index deque up to maximum usize"]
+fn ring_range_overflow() {
+    let buf = wrapped();
+    buf.index(1..=usize::MAX);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn btree_map_borrowed() {
+    use alloc::string::{String, ToString};
+
+    let mut map: BTreeMap<(String, String), u32> = BTreeMap::new();
+    map.insert(("a".to_string(), "b".to_string()), 1);
+    map.insert(("a".to_string(), "c".to_string()), 2);
+    assert_eq!(map.insert(("a".to_string(), "b".to_string()), 3), Some(1));
+
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.get(("a", "b")), Some(&3));
+    assert_eq!(map.get(("b", "a")), None);
+    *map.get_mut(("a", "c")).unwrap() += 1;
+    assert_eq!(map.remove(("a", "c")), Some(3));
+    assert!(!map.contains_key(("a", "c")));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn btree_map_static_borrows() {
+    use alloc::{vec, vec::Vec};
+
+    // Keys holding borrows need `BorrowOrd`, which is only implemented for `'static` types
+    let mut map: BTreeMap<(&'static str, Vec<&'static str>), u32> = BTreeMap::new();
+    map.insert(("a", vec!["b"]), 1);
+    assert_eq!(map.get(("a", &["b"][..])), Some(&1));
+    assert_eq!(map.get(("a", &[][..])), None);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn hash_map_borrowed() {
+    use alloc::{vec, vec::Vec};
+
+    let mut map: HashMap<(Vec<u8>, u32), &str> = HashMap::new();
+    map.insert((vec![1, 2], 3), "x");
+    map.insert((vec![1], 3), "y");
+
+    assert_eq!(map.get((&[1, 2], 3)), Some(&"x"));
+    assert_eq!(map.get((&[1], 3)), Some(&"y"));
+    assert_eq!(map.get((&[1], 4)), None);
+    assert_eq!(map.remove((&[1, 2], 3)), Some("x"));
+    assert_eq!(map.len(), 1);
+}
+
+fn view_sum<'a, V>(view: V) -> u8
+where
+    V: crate::convert::IntoView<[u8]>,
+    V::View: core::iter::IntoIterator<Item = &'a u8>,
+{
+    view.into_view().into_iter().copied().sum()
+}
+
+#[test]
+fn ring_as_view() {
+    use crate::convert::{AsView, AsViewMut};
+
+    let mut ring = wrapped();
+    assert_eq!(view_sum(&ring), 27);
+    assert_eq!(view_sum(&[1u8, 2, 3]), 6);
+    assert_eq!(view_sum(ring.as_view().index(1..3)), 7);
+
+    let mut view = ring.as_view_mut();
+    *view.index_mut(2..).get_mut(0).unwrap() = 10;
+    assert_eq!(ring.as_view(), DequeRange::from_slices(&[2, 3, 10, 5], &[6, 7]));
+}
+
+fn view_at<V>(view: V, idx: usize) -> u8
+where
+    V: crate::convert::IntoView<[u8]>,
+    V::View: core::ops::Index<usize, Output = u8>,
+{
+    *view.into_view().index(idx)
+}
+
+/// Lends each of its rings in turn
+struct Rings([RingBuffer<u8, 6>; 2], usize);
+
+impl Iterator for Rings {
+    type Item<'a> = &'a mut RingBuffer<u8, 6>;
+
+    fn next(&mut self) -> Option<Self::Item<'_>> {
+        let out = self.0.get_mut(self.1)?;
+        self.1 += 1;
+        Some(out)
+    }
+}
+
+#[test]
+fn ring_views() {
+    let mut views = Rings([wrapped(), wrapped()], 0).views::<[u8]>();
+
+    let mut first = views.next().unwrap();
+    *first.index_mut(0) = 10;
+    assert_eq!(view_at(first, 0), 10);
+    let second = views.next().unwrap();
+    assert_eq!(view_at(second, 0), 2);
+    assert!(views.next().is_none());
+
+    assert_eq!(view_at(&wrapped(), 5), 7);
+}
+
+#[cfg(feature = "alloc")]
+fn count_words<'a, M>(map: &mut M, words: &[&'a str])
+where
+    M: Entry<&'a str, Value = usize>,
+{
+    for word in words {
+        *map.entry(word).or_default() += 1;
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn collection_traits() {
+    use alloc::collections::{BTreeMap, VecDeque};
+    use alloc::vec;
+
+    let mut list = vec![1, 2, 3];
+    Insert::insert(&mut list, 1, 5);
+    assert_eq!(Get::get(&list, 1), Some(&5));
+    assert_eq!(Remove::remove(&mut list, 10), None);
+    assert_eq!(Remove::remove(&mut list, 0), Some(1));
+
+    let mut deque = VecDeque::from(list);
+    *GetMut::get_mut(&mut deque, 2).unwrap() = 4;
+    assert!(Get::contains(&deque, 2));
+    assert!(!Get::contains(&deque, 3));
+
+    let mut bits = BitVec::from_elem(false, 4);
+    GetMut::get_mut(&mut bits, 1).unwrap().set(true);
+    assert_eq!(Get::get(&bits, 1), Some(true));
+
+    let mut counts = BTreeMap::new();
+    count_words(&mut counts, &["a", "b", "a"]);
+    assert_eq!(Get::get(&counts, "a"), Some(&2));
+    assert!(Entry::entry(&mut counts, "b").is_occupied());
+    assert!(!Entry::entry(&mut counts, "c").is_occupied());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn vec_edit_cursor() {
+    use alloc::string::{String, ToString};
+
+    let mut vec: alloc::vec::Vec<String> = ["a", "b", "c", "d"].map(String::from).to_vec();
+    let mut cursor = EditCursor::new(&mut vec);
+    assert_eq!(cursor.size_hint(), (4, Some(4)));
+    while let Some(mut entry) = cursor.next() {
+        match entry.get().as_str() {
+            "a" => {
+                entry.insert_before("<".to_string());
+                entry.insert_after("1".to_string());
+                entry.insert_after("2".to_string());
+            }
+            "b" => assert_eq!(entry.remove(), "b"),
+            "c" => {
+                entry.insert_after("3".to_string());
+                entry.insert_before("4".to_string());
+                assert_eq!(entry.replace("C".to_string()), "c");
+                entry.get_mut().push('!');
+            }
+            _ => break,
+        }
+    }
+    drop(cursor);
+    assert_eq!(vec, ["<", "a", "1", "2", "4", "C!", "3", "d"]);
+
+    let mut cursor = EditCursor::new(&mut vec);
+    while let Some(entry) = cursor.next() {
+        if entry.get().len() == 1 {
+            entry.remove();
+        }
+    }
+    drop(cursor);
+    assert_eq!(vec, ["C!"]);
+}
@@ -0,0 +1,473 @@
+#[cfg(feature = "std")]
+use core::hash::{BuildHasher, Hash};
+
+#[cfg(feature = "alloc")]
+use alloc::collections::{btree_map, VecDeque};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::collections::hash_map;
+
+#[cfg(feature = "alloc")]
+use super::{BTreeMap, BitMut, BitVec};
+#[cfg(feature = "std")]
+use super::HashMap;
+use super::RingBuffer;
+#[cfg(feature = "alloc")]
+use crate::borrow::BorrowOrd;
+#[cfg(feature = "std")]
+use crate::borrow::BorrowHash;
+
+/// Non-panicking immutable access to a collection by key. As opposed to [`ops::Index`], this
+/// returns `None` for missing keys.
+///
+/// [`ops::Index`]: crate::ops::Index
+pub trait Get<K> {
+    /// The output type of looking up a key
+    type Output<'a>
+    where
+        Self: 'a;
+
+    /// Get the value for `key`, or `None` if it isn't present
+    fn get(&self, key: K) -> Option<Self::Output<'_>>;
+
+    /// Check whether this collection contains a value for `key`
+    fn contains(&self, key: K) -> bool {
+        self.get(key).is_some()
+    }
+}
+
+/// Non-panicking mutable access to a collection by key. As opposed to [`ops::IndexMut`], this
+/// returns `None` for missing keys.
+///
+/// [`ops::IndexMut`]: crate::ops::IndexMut
+pub trait GetMut<K>: Get<K> {
+    /// The output type of looking up a key mutably
+    type OutputMut<'a>
+    where
+        Self: 'a;
+
+    /// Get the value for `key` mutably, or `None` if it isn't present
+    fn get_mut(&mut self, key: K) -> Option<Self::OutputMut<'_>>;
+}
+
+/// Insertion of a value into a collection at a key
+pub trait Insert<K, V> {
+    /// Insert `val` at `key`, returning the value it replaced, if any.
+    ///
+    /// For sequences, `key` is a position, and existing items are shifted over rather than
+    /// replaced, so this always returns `None`.
+    ///
+    /// # Panics
+    ///
+    /// For sequences, if `key` is greater than the length
+    fn insert(&mut self, key: K, val: V) -> Option<V>;
+}
+
+/// Removal of a value from a collection by key
+pub trait Remove<K> {
+    /// The type of the removed value
+    type Output;
+
+    /// Remove the value at `key`, or return `None` if it isn't present. For sequences, later
+    /// items are shifted over to fill the gap.
+    fn remove(&mut self, key: K) -> Option<Self::Output>;
+}
+
+/// Entry-based access to a collection, allowing in-place manipulation of a value which may or
+/// may not be present. The entry mutably borrows the collection for its lifetime.
+pub trait Entry<K> {
+    /// The type of values in this collection
+    type Value;
+
+    /// The entry type for this collection
+    type Handle<'a>: EntryHandle<'a, Value = Self::Value>
+    where
+        Self: 'a;
+
+    /// Get the entry for `key`
+    fn entry(&mut self, key: K) -> Self::Handle<'_>;
+}
+
+/// A view into a single entry of a collection, which may be occupied or vacant. See [`Entry`].
+pub trait EntryHandle<'a>: Sized {
+    /// The type of the value in this entry
+    type Value: 'a;
+
+    /// Check whether this entry contains a value
+    fn is_occupied(&self) -> bool;
+
+    /// Get the value in this entry, or `None` if it is vacant
+    fn get(&self) -> Option<&Self::Value>;
+
+    /// Insert `default` if this entry is vacant, then return a mutable reference to the value
+    fn or_insert(self, default: Self::Value) -> &'a mut Self::Value;
+
+    /// Insert the result of `f` if this entry is vacant, then return a mutable reference to the
+    /// value
+    fn or_insert_with<F: FnOnce() -> Self::Value>(self, f: F) -> &'a mut Self::Value;
+
+    /// Insert the default value if this entry is vacant, then return a mutable reference to the
+    /// value
+    fn or_default(self) -> &'a mut Self::Value
+    where
+        Self::Value: Default,
+    {
+        self.or_insert_with(Default::default)
+    }
+
+    /// Modify the value in this entry if it is occupied
+    fn and_modify<F: FnOnce(&mut Self::Value)>(self, f: F) -> Self;
+}
+
+macro_rules! impl_seq {
+    ($($(#[$meta:meta])* [$($gen:tt)*] $ty:ty => $inherent:ty),* $(,)?) => {
+        $(
+        $(#[$meta])*
+        impl<$($gen)*> Get<usize> for $ty {
+            type Output<'a> = &'a T
+            where
+                Self: 'a;
+
+            fn get(&self, key: usize) -> Option<Self::Output<'_>> {
+                <$inherent>::get(self, key)
+            }
+        }
+
+        $(#[$meta])*
+        impl<$($gen)*> GetMut<usize> for $ty {
+            type OutputMut<'a> = &'a mut T
+            where
+                Self: 'a;
+
+            fn get_mut(&mut self, key: usize) -> Option<Self::OutputMut<'_>> {
+                <$inherent>::get_mut(self, key)
+            }
+        }
+        )*
+    };
+}
+
+impl_seq!(
+    [T] [T] => [T],
+    #[cfg(feature = "alloc")]
+    [T] Vec<T> => [T],
+    #[cfg(feature = "alloc")]
+    [T] VecDeque<T> => VecDeque<T>,
+    [T, const N: usize] RingBuffer<T, N> => RingBuffer<T, N>,
+);
+
+#[cfg(feature = "alloc")]
+impl<T> Insert<usize, T> for Vec<T> {
+    fn insert(&mut self, key: usize, val: T) -> Option<T> {
+        Vec::insert(self, key, val);
+        None
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> Remove<usize> for Vec<T> {
+    type Output = T;
+
+    fn remove(&mut self, key: usize) -> Option<Self::Output> {
+        if key < self.len() {
+            Some(Vec::remove(self, key))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> Insert<usize, T> for VecDeque<T> {
+    fn insert(&mut self, key: usize, val: T) -> Option<T> {
+        VecDeque::insert(self, key, val);
+        None
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> Remove<usize> for VecDeque<T> {
+    type Output = T;
+
+    fn remove(&mut self, key: usize) -> Option<Self::Output> {
+        VecDeque::remove(self, key)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Get<usize> for BitVec {
+    type Output<'a> = bool;
+
+    fn get(&self, key: usize) -> Option<Self::Output<'_>> {
+        BitVec::get(self, key)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl GetMut<usize> for BitVec {
+    type OutputMut<'a> = BitMut<'a>;
+
+    fn get_mut(&mut self, key: usize) -> Option<Self::OutputMut<'_>> {
+        BitVec::get_mut(self, key)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<K, V, Q> Get<&Q> for btree_map::BTreeMap<K, V>
+where
+    K: Ord + core::borrow::Borrow<Q>,
+    Q: ?Sized + Ord,
+{
+    type Output<'a> = &'a V
+    where
+        Self: 'a;
+
+    fn get(&self, key: &Q) -> Option<Self::Output<'_>> {
+        btree_map::BTreeMap::get(self, key)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<K, V, Q> GetMut<&Q> for btree_map::BTreeMap<K, V>
+where
+    K: Ord + core::borrow::Borrow<Q>,
+    Q: ?Sized + Ord,
+{
+    type OutputMut<'a> = &'a mut V
+    where
+        Self: 'a;
+
+    fn get_mut(&mut self, key: &Q) -> Option<Self::OutputMut<'_>> {
+        btree_map::BTreeMap::get_mut(self, key)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<K: Ord, V> Insert<K, V> for btree_map::BTreeMap<K, V> {
+    fn insert(&mut self, key: K, val: V) -> Option<V> {
+        btree_map::BTreeMap::insert(self, key, val)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<K, V, Q> Remove<&Q> for btree_map::BTreeMap<K, V>
+where
+    K: Ord + core::borrow::Borrow<Q>,
+    Q: ?Sized + Ord,
+{
+    type Output = V;
+
+    fn remove(&mut self, key: &Q) -> Option<Self::Output> {
+        btree_map::BTreeMap::remove(self, key)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<K: Ord, V> Entry<K> for btree_map::BTreeMap<K, V> {
+    type Value = V;
+    type Handle<'a> = btree_map::Entry<'a, K, V>
+    where
+        Self: 'a;
+
+    fn entry(&mut self, key: K) -> Self::Handle<'_> {
+        btree_map::BTreeMap::entry(self, key)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, K: Ord, V> EntryHandle<'a> for btree_map::Entry<'a, K, V> {
+    type Value = V;
+
+    fn is_occupied(&self) -> bool {
+        matches!(self, btree_map::Entry::Occupied(_))
+    }
+
+    fn get(&self) -> Option<&Self::Value> {
+        match self {
+            btree_map::Entry::Occupied(entry) => Some(entry.get()),
+            btree_map::Entry::Vacant(_) => None,
+        }
+    }
+
+    fn or_insert(self, default: Self::Value) -> &'a mut Self::Value {
+        btree_map::Entry::or_insert(self, default)
+    }
+
+    fn or_insert_with<F: FnOnce() -> Self::Value>(self, f: F) -> &'a mut Self::Value {
+        btree_map::Entry::or_insert_with(self, f)
+    }
+
+    fn and_modify<F: FnOnce(&mut Self::Value)>(self, f: F) -> Self {
+        btree_map::Entry::and_modify(self, f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K, V, S, Q> Get<&Q> for hash_map::HashMap<K, V, S>
+where
+    K: Eq + Hash + core::borrow::Borrow<Q>,
+    Q: ?Sized + Eq + Hash,
+    S: BuildHasher,
+{
+    type Output<'a> = &'a V
+    where
+        Self: 'a;
+
+    fn get(&self, key: &Q) -> Option<Self::Output<'_>> {
+        hash_map::HashMap::get(self, key)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K, V, S, Q> GetMut<&Q> for hash_map::HashMap<K, V, S>
+where
+    K: Eq + Hash + core::borrow::Borrow<Q>,
+    Q: ?Sized + Eq + Hash,
+    S: BuildHasher,
+{
+    type OutputMut<'a> = &'a mut V
+    where
+        Self: 'a;
+
+    fn get_mut(&mut self, key: &Q) -> Option<Self::OutputMut<'_>> {
+        hash_map::HashMap::get_mut(self, key)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K: Eq + Hash, V, S: BuildHasher> Insert<K, V> for hash_map::HashMap<K, V, S> {
+    fn insert(&mut self, key: K, val: V) -> Option<V> {
+        hash_map::HashMap::insert(self, key, val)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K, V, S, Q> Remove<&Q> for hash_map::HashMap<K, V, S>
+where
+    K: Eq + Hash + core::borrow::Borrow<Q>,
+    Q: ?Sized + Eq + Hash,
+    S: BuildHasher,
+{
+    type Output = V;
+
+    fn remove(&mut self, key: &Q) -> Option<Self::Output> {
+        hash_map::HashMap::remove(self, key)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K: Eq + Hash, V, S: BuildHasher> Entry<K> for hash_map::HashMap<K, V, S> {
+    type Value = V;
+    type Handle<'a> = hash_map::Entry<'a, K, V>
+    where
+        Self: 'a;
+
+    fn entry(&mut self, key: K) -> Self::Handle<'_> {
+        hash_map::HashMap::entry(self, key)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, K, V> EntryHandle<'a> for hash_map::Entry<'a, K, V> {
+    type Value = V;
+
+    fn is_occupied(&self) -> bool {
+        matches!(self, hash_map::Entry::Occupied(_))
+    }
+
+    fn get(&self) -> Option<&Self::Value> {
+        match self {
+            hash_map::Entry::Occupied(entry) => Some(entry.get()),
+            hash_map::Entry::Vacant(_) => None,
+        }
+    }
+
+    fn or_insert(self, default: Self::Value) -> &'a mut Self::Value {
+        hash_map::Entry::or_insert(self, default)
+    }
+
+    fn or_insert_with<F: FnOnce() -> Self::Value>(self, f: F) -> &'a mut Self::Value {
+        hash_map::Entry::or_insert_with(self, f)
+    }
+
+    fn and_modify<F: FnOnce(&mut Self::Value)>(self, f: F) -> Self {
+        hash_map::Entry::and_modify(self, f)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'q, K: BorrowOrd + 'q, V> Get<K::Borrowed<'q>> for BTreeMap<K, V> {
+    type Output<'a> = &'a V
+    where
+        Self: 'a;
+
+    fn get(&self, key: K::Borrowed<'q>) -> Option<Self::Output<'_>> {
+        BTreeMap::get(self, key)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'q, K: BorrowOrd + 'q, V> GetMut<K::Borrowed<'q>> for BTreeMap<K, V> {
+    type OutputMut<'a> = &'a mut V
+    where
+        Self: 'a;
+
+    fn get_mut(&mut self, key: K::Borrowed<'q>) -> Option<Self::OutputMut<'_>> {
+        BTreeMap::get_mut(self, key)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<K: BorrowOrd, V> Insert<K, V> for BTreeMap<K, V> {
+    fn insert(&mut self, key: K, val: V) -> Option<V> {
+        BTreeMap::insert(self, key, val)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'q, K: BorrowOrd + 'q, V> Remove<K::Borrowed<'q>> for BTreeMap<K, V> {
+    type Output = V;
+
+    fn remove(&mut self, key: K::Borrowed<'q>) -> Option<Self::Output> {
+        BTreeMap::remove(self, key)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'q, K: BorrowHash + 'q, V, S: BuildHasher> Get<K::Borrowed<'q>> for HashMap<K, V, S> {
+    type Output<'a> = &'a V
+    where
+        Self: 'a;
+
+    fn get(&self, key: K::Borrowed<'q>) -> Option<Self::Output<'_>> {
+        HashMap::get(self, key)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'q, K: BorrowHash + 'q, V, S: BuildHasher> GetMut<K::Borrowed<'q>> for HashMap<K, V, S> {
+    type OutputMut<'a> = &'a mut V
+    where
+        Self: 'a;
+
+    fn get_mut(&mut self, key: K::Borrowed<'q>) -> Option<Self::OutputMut<'_>> {
+        HashMap::get_mut(self, key)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K: BorrowHash, V, S: BuildHasher> Insert<K, V> for HashMap<K, V, S> {
+    fn insert(&mut self, key: K, val: V) -> Option<V> {
+        HashMap::insert(self, key, val)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'q, K: BorrowHash + 'q, V, S: BuildHasher> Remove<K::Borrowed<'q>> for HashMap<K, V, S> {
+    type Output = V;
+
+    fn remove(&mut self, key: K::Borrowed<'q>) -> Option<Self::Output> {
+        HashMap::remove(self, key)
+    }
+}
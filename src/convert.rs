@@ -0,0 +1,95 @@
+//! GAT equivalents of `std` conversion traits
+
+/// Cheap reference-to-view conversion. As opposed to `std`'s `AsRef`, the returned value can be a
+/// non-reference. This allows things like a ring buffer viewing its items as a
+/// [`DequeRange`](crate::collections::DequeRange) instead of a slice.
+///
+/// Functions accepting views can index them with [`ops::Index`](crate::ops::Index), and lending
+/// iterators can convert their items into views with
+/// [`Iterator::views`](crate::iter::Iterator::views).
+/// `AsView` types aren't indexable themselves, as a blanket `ops::Index` impl for them would
+/// overlap with the one for [`core::ops::Index`] - index the view instead.
+pub trait AsView<T: ?Sized> {
+    /// The view type produced by this conversion
+    type View<'a>
+    where
+        Self: 'a;
+
+    /// Convert this value into a view immutably
+    fn as_view(&self) -> Self::View<'_>;
+}
+
+/// Cheap mutable-reference-to-view conversion. As opposed to `std`'s `AsMut`, the returned value
+/// can be a non-reference.
+pub trait AsViewMut<T: ?Sized>: AsView<T> {
+    /// The view type produced by this conversion
+    type ViewMut<'a>
+    where
+        Self: 'a;
+
+    /// Convert this value into a view mutably
+    fn as_view_mut(&mut self) -> Self::ViewMut<'_>;
+}
+
+impl<T: ?Sized, U> AsView<U> for T
+where
+    T: core::convert::AsRef<U>,
+    U: ?Sized + 'static,
+{
+    type View<'a> = &'a U
+    where
+        Self: 'a;
+
+    fn as_view(&self) -> Self::View<'_> {
+        <Self as core::convert::AsRef<U>>::as_ref(self)
+    }
+}
+
+impl<T: ?Sized, U> AsViewMut<U> for T
+where
+    T: core::convert::AsRef<U> + core::convert::AsMut<U>,
+    U: ?Sized + 'static,
+{
+    type ViewMut<'a> = &'a mut U
+    where
+        Self: 'a;
+
+    fn as_view_mut(&mut self) -> Self::ViewMut<'_> {
+        <Self as core::convert::AsMut<U>>::as_mut(self)
+    }
+}
+
+/// Conversion of a value into a view, consuming it. This is implemented for references to
+/// [`AsView`] and [`AsViewMut`] types, and for view types themselves, making it the most general
+/// bound for functions that accept views.
+pub trait IntoView<T: ?Sized> {
+    /// The view type produced by this conversion
+    type View;
+
+    /// Convert this value into a view
+    fn into_view(self) -> Self::View;
+}
+
+impl<'a, T, U> IntoView<U> for &'a T
+where
+    T: ?Sized + AsView<U>,
+    U: ?Sized,
+{
+    type View = T::View<'a>;
+
+    fn into_view(self) -> Self::View {
+        self.as_view()
+    }
+}
+
+impl<'a, T, U> IntoView<U> for &'a mut T
+where
+    T: ?Sized + AsViewMut<U>,
+    U: ?Sized,
+{
+    type View = T::ViewMut<'a>;
+
+    fn into_view(self) -> Self::View {
+        self.as_view_mut()
+    }
+}
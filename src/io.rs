@@ -0,0 +1,19 @@
+//! GAT equivalents of `std` I/O traits, and buffer-reusing readers which lend each record from a
+//! single internal buffer instead of allocating a new one per item.
+//!
+//! [`LendingRead`] is available without `std`, for things like UART or DMA buffers. With the
+//! `std` feature, the readers from [`ReadExt`] and [`BufReadExt`] implement
+//! [`TryIterator`](crate::iter::TryIterator), and convert into a lending
+//! [`Iterator`](crate::iter::Iterator) over `Result` items via
+//! [`IntoIterator`](crate::iter::IntoIterator), so they work in `#[gatify]` for-loops.
+
+mod lending;
+#[cfg(feature = "std")]
+mod reusing;
+
+pub use lending::*;
+#[cfg(feature = "std")]
+pub use reusing::*;
+
+#[cfg(test)]
+mod test;
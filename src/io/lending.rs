@@ -0,0 +1,315 @@
+use core::convert::Infallible;
+use core::fmt;
+use core::ops::{Deref, Range};
+
+use crate::collections::RingBuffer;
+use crate::iter::Iterator;
+
+/// A reader which lends windows of its internal buffer, like `std`'s `BufRead::fill_buf` and
+/// `consume`. As opposed to `std`, the window type is a GAT, so it can be a guard type instead of
+/// a plain slice, and this trait is available without `std`.
+pub trait LendingRead {
+    /// The window type lent by [`LendingRead::fill_buf`]
+    type Window<'a>: Deref<Target = [u8]>
+    where
+        Self: 'a;
+
+    /// The error which may occur while reading
+    type Error;
+
+    /// Get the bytes currently available in this reader. An empty window means no bytes are
+    /// available yet, or the reader is exhausted.
+    fn fill_buf(&mut self) -> Result<Self::Window<'_>, Self::Error>;
+
+    /// Mark `amt` bytes of the window as read, so they won't be returned by `fill_buf` again.
+    /// This is clamped to the number of available bytes.
+    fn consume(&mut self, amt: usize);
+
+    /// Get the most bytes `fill_buf` can lend at once, or `None` if it is unbounded. The framing
+    /// iterators truncate frames which can't fit into this many bytes, instead of waiting for them
+    /// forever.
+    fn capacity(&self) -> Option<usize> {
+        None
+    }
+
+    /// Get an iterator over frames terminated by `delim`. The delimiter is not included in the
+    /// frames.
+    ///
+    /// The iterator returns `None` while no complete frame is available, and continues if more
+    /// data arrives. Trailing bytes without a delimiter are left in the reader. If the reader
+    /// fills up to its [`capacity`](LendingRead::capacity) without a delimiter, its contents are
+    /// lent as a truncated frame, and the rest of that frame is skipped.
+    fn delimited(self, delim: u8) -> Delimited<Self>
+    where
+        Self: Sized,
+    {
+        Delimited::new(self, delim)
+    }
+
+    /// Get an iterator over frames prefixed by their big-endian length, `width` bytes wide. The
+    /// prefix is not included in the frames.
+    ///
+    /// The iterator returns `None` while no complete frame is available, and continues if more
+    /// data arrives. A frame longer than the reader's [`capacity`](LendingRead::capacity) is
+    /// lent truncated once the reader fills up, and the rest of that frame is skipped.
+    ///
+    /// # Panics
+    ///
+    /// If `width` is zero or wider than a `usize`
+    fn length_prefixed(self, width: usize) -> LengthPrefixed<Self>
+    where
+        Self: Sized,
+    {
+        assert!(width > 0 && width <= core::mem::size_of::<usize>());
+        LengthPrefixed::new(self, width)
+    }
+}
+
+impl<R: ?Sized + LendingRead> LendingRead for &mut R {
+    type Window<'a> = R::Window<'a>
+    where
+        Self: 'a;
+
+    type Error = R::Error;
+
+    fn fill_buf(&mut self) -> Result<Self::Window<'_>, Self::Error> {
+        (**self).fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        (**self).consume(amt)
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        (**self).capacity()
+    }
+}
+
+impl LendingRead for &[u8] {
+    type Window<'a> = &'a [u8]
+    where
+        Self: 'a;
+
+    type Error = Infallible;
+
+    fn fill_buf(&mut self) -> Result<Self::Window<'_>, Self::Error> {
+        Ok(*self)
+    }
+
+    fn consume(&mut self, amt: usize) {
+        *self = &self[amt.min(self.len())..];
+    }
+}
+
+impl<const N: usize> LendingRead for RingBuffer<u8, N> {
+    type Window<'a> = &'a [u8];
+
+    type Error = Infallible;
+
+    fn fill_buf(&mut self) -> Result<Self::Window<'_>, Self::Error> {
+        Ok(self.make_contiguous())
+    }
+
+    fn consume(&mut self, amt: usize) {
+        for _ in 0..amt {
+            if self.pop_front().is_none() {
+                break;
+            }
+        }
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        Some(N)
+    }
+}
+
+/// A frame lent by the iterators from [`LendingRead`]. Dereferences to the bytes of the frame.
+pub struct Frame<W> {
+    window: W,
+    range: Range<usize>,
+}
+
+impl<W: Deref<Target = [u8]>> Deref for Frame<W> {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        &self.window[self.range.clone()]
+    }
+}
+
+impl<W: Deref<Target = [u8]>> fmt::Debug for Frame<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+impl<W: Deref<Target = [u8]>> PartialEq<[u8]> for Frame<W> {
+    fn eq(&self, other: &[u8]) -> bool {
+        **self == *other
+    }
+}
+
+/// See [`LendingRead::delimited`]
+pub struct Delimited<R> {
+    reader: R,
+    delim: u8,
+    pending: usize,
+    truncated: bool,
+}
+
+impl<R> Delimited<R> {
+    pub(crate) fn new(reader: R, delim: u8) -> Delimited<R> {
+        Delimited {
+            reader,
+            delim,
+            pending: 0,
+            truncated: false,
+        }
+    }
+
+    /// Get a reference to the underlying reader. The last lent frame is still in its buffer.
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+}
+
+impl<R: LendingRead> Delimited<R> {
+    /// Get a mutable reference to the underlying reader, consuming any lent frame
+    pub fn get_mut(&mut self) -> &mut R {
+        self.reader.consume(core::mem::take(&mut self.pending));
+        &mut self.reader
+    }
+
+    /// Get the underlying reader, consuming any lent frame
+    pub fn into_inner(mut self) -> R {
+        self.reader.consume(self.pending);
+        self.reader
+    }
+}
+
+impl<R: LendingRead> Iterator for Delimited<R> {
+    type Item<'a> = Result<Frame<R::Window<'a>>, R::Error>
+    where
+        Self: 'a;
+
+    fn next(&mut self) -> Option<Self::Item<'_>> {
+        self.reader.consume(core::mem::take(&mut self.pending));
+        if self.truncated {
+            // Skip the rest of a truncated frame, up to and including its delimiter
+            let (skip, found) = match self.reader.fill_buf() {
+                Ok(window) => match window.iter().position(|b| *b == self.delim) {
+                    Some(len) => (len + 1, true),
+                    None => (window.len(), false),
+                },
+                Err(e) => return Some(Err(e)),
+            };
+            self.reader.consume(skip);
+            if !found {
+                return None;
+            }
+            self.truncated = false;
+        }
+
+        let capacity = self.reader.capacity();
+        let window = match self.reader.fill_buf() {
+            Ok(window) => window,
+            Err(e) => return Some(Err(e)),
+        };
+        let range = match window.iter().position(|b| *b == self.delim) {
+            Some(len) => {
+                self.pending = len + 1;
+                0..len
+            }
+            None if capacity.is_some_and(|cap| window.len() >= cap) => {
+                self.pending = window.len();
+                self.truncated = true;
+                0..window.len()
+            }
+            None => return None,
+        };
+        Some(Ok(Frame { window, range }))
+    }
+}
+
+/// See [`LendingRead::length_prefixed`]
+pub struct LengthPrefixed<R> {
+    reader: R,
+    width: usize,
+    pending: usize,
+    skip: usize,
+}
+
+impl<R> LengthPrefixed<R> {
+    pub(crate) fn new(reader: R, width: usize) -> LengthPrefixed<R> {
+        LengthPrefixed {
+            reader,
+            width,
+            pending: 0,
+            skip: 0,
+        }
+    }
+
+    /// Get a reference to the underlying reader. The last lent frame is still in its buffer.
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+}
+
+impl<R: LendingRead> LengthPrefixed<R> {
+    /// Get a mutable reference to the underlying reader, consuming any lent frame
+    pub fn get_mut(&mut self) -> &mut R {
+        self.reader.consume(core::mem::take(&mut self.pending));
+        &mut self.reader
+    }
+
+    /// Get the underlying reader, consuming any lent frame
+    pub fn into_inner(mut self) -> R {
+        self.reader.consume(self.pending);
+        self.reader
+    }
+}
+
+impl<R: LendingRead> Iterator for LengthPrefixed<R> {
+    type Item<'a> = Result<Frame<R::Window<'a>>, R::Error>
+    where
+        Self: 'a;
+
+    fn next(&mut self) -> Option<Self::Item<'_>> {
+        self.reader.consume(core::mem::take(&mut self.pending));
+        if self.skip > 0 {
+            // Skip the rest of a truncated frame
+            let skip = match self.reader.fill_buf() {
+                Ok(window) => window.len().min(self.skip),
+                Err(e) => return Some(Err(e)),
+            };
+            self.reader.consume(skip);
+            self.skip -= skip;
+            if self.skip > 0 {
+                return None;
+            }
+        }
+
+        let capacity = self.reader.capacity();
+        let window = match self.reader.fill_buf() {
+            Ok(window) => window,
+            Err(e) => return Some(Err(e)),
+        };
+        let len = window
+            .get(..self.width)?
+            .iter()
+            .fold(0, |acc, b| (acc << 8) | usize::from(*b));
+        let mut end = self.width.saturating_add(len);
+        if window.len() < end {
+            if capacity.is_none_or(|cap| window.len() < cap) {
+                return None;
+            }
+            self.skip = end - window.len();
+            end = window.len();
+        }
+        self.pending = end;
+        Some(Ok(Frame {
+            window,
+            range: self.width..end,
+        }))
+    }
+}
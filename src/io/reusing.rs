@@ -0,0 +1,203 @@
+use std::io::{self, BufRead, ErrorKind, Read};
+use std::string::String;
+use std::vec;
+use std::vec::Vec;
+
+use crate::iter::{IntoIterator, IntoResults, TryIterator};
+
+/// Extension methods for [`Read`] which lend data from an internal buffer
+pub trait ReadExt: Read + Sized {
+    /// Get an iterator over chunks of `n` bytes read from this reader. The last chunk may be
+    /// shorter if the reader ends first. If reading fails partway through a chunk, the bytes read
+    /// so far are returned as a shorter chunk, and the error on the next call.
+    ///
+    /// # Panics
+    ///
+    /// If `n` is zero
+    fn read_chunks(self, n: usize) -> ReadChunks<Self> {
+        assert_ne!(n, 0);
+        ReadChunks::new(self, n)
+    }
+}
+
+impl<R: Read> ReadExt for R {}
+
+/// Extension methods for [`BufRead`] which lend data from an internal buffer
+pub trait BufReadExt: BufRead + Sized {
+    /// Get an iterator over the lines of this reader, like [`BufRead::lines`]. Each line has its
+    /// trailing newline or carriage return and newline removed.
+    fn lines_reusing(self) -> LinesReusing<Self> {
+        LinesReusing::new(self)
+    }
+
+    /// Get an iterator over the contents of this reader split on `delim`, like
+    /// [`BufRead::split`]. Each record has its trailing delimiter removed.
+    fn split_reusing(self, delim: u8) -> SplitReusing<Self> {
+        SplitReusing::new(self, delim)
+    }
+}
+
+impl<R: BufRead> BufReadExt for R {}
+
+/// See [`BufReadExt::lines_reusing`]
+pub struct LinesReusing<R> {
+    reader: R,
+    buf: String,
+}
+
+impl<R> LinesReusing<R> {
+    fn new(reader: R) -> LinesReusing<R> {
+        LinesReusing {
+            reader,
+            buf: String::new(),
+        }
+    }
+
+    /// Get the underlying reader
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: BufRead> TryIterator for LinesReusing<R> {
+    type Item<'a> = &'a mut String
+    where
+        Self: 'a;
+
+    type Error = io::Error;
+
+    fn try_next(&mut self) -> Result<Option<Self::Item<'_>>, Self::Error> {
+        self.buf.clear();
+        if self.reader.read_line(&mut self.buf)? == 0 {
+            return Ok(None);
+        }
+        if self.buf.ends_with('\n') {
+            self.buf.pop();
+            if self.buf.ends_with('\r') {
+                self.buf.pop();
+            }
+        }
+        Ok(Some(&mut self.buf))
+    }
+}
+
+impl<R: BufRead> IntoIterator for LinesReusing<R> {
+    type IntoIter = IntoResults<Self>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.into_results()
+    }
+}
+
+/// See [`BufReadExt::split_reusing`]
+pub struct SplitReusing<R> {
+    reader: R,
+    delim: u8,
+    buf: Vec<u8>,
+}
+
+impl<R> SplitReusing<R> {
+    fn new(reader: R, delim: u8) -> SplitReusing<R> {
+        SplitReusing {
+            reader,
+            delim,
+            buf: Vec::new(),
+        }
+    }
+
+    /// Get the underlying reader
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: BufRead> TryIterator for SplitReusing<R> {
+    type Item<'a> = &'a [u8]
+    where
+        Self: 'a;
+
+    type Error = io::Error;
+
+    fn try_next(&mut self) -> Result<Option<Self::Item<'_>>, Self::Error> {
+        self.buf.clear();
+        if self.reader.read_until(self.delim, &mut self.buf)? == 0 {
+            return Ok(None);
+        }
+        if self.buf.last() == Some(&self.delim) {
+            self.buf.pop();
+        }
+        Ok(Some(&self.buf))
+    }
+}
+
+impl<R: BufRead> IntoIterator for SplitReusing<R> {
+    type IntoIter = IntoResults<Self>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.into_results()
+    }
+}
+
+/// See [`ReadExt::read_chunks`]
+pub struct ReadChunks<R> {
+    reader: R,
+    buf: Vec<u8>,
+    /// An error hit after reading part of a chunk, returned on the next call
+    error: Option<io::Error>,
+}
+
+impl<R> ReadChunks<R> {
+    fn new(reader: R, n: usize) -> ReadChunks<R> {
+        ReadChunks {
+            reader,
+            buf: vec![0; n],
+            error: None,
+        }
+    }
+
+    /// Get the underlying reader
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: Read> TryIterator for ReadChunks<R> {
+    type Item<'a> = &'a [u8]
+    where
+        Self: 'a;
+
+    type Error = io::Error;
+
+    fn try_next(&mut self) -> Result<Option<Self::Item<'_>>, Self::Error> {
+        if let Some(e) = self.error.take() {
+            return Err(e);
+        }
+
+        let mut filled = 0;
+        while filled < self.buf.len() {
+            match self.reader.read(&mut self.buf[filled..]) {
+                Ok(0) => break,
+                Ok(n) => filled += n,
+                Err(e) if e.kind() == ErrorKind::Interrupted => (),
+                // Like `BufRead::read_line`, return what was read before the error
+                Err(e) if filled > 0 => {
+                    self.error = Some(e);
+                    break;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        if filled == 0 {
+            return Ok(None);
+        }
+        Ok(Some(&self.buf[..filled]))
+    }
+}
+
+impl<R: Read> IntoIterator for ReadChunks<R> {
+    type IntoIter = IntoResults<Self>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.into_results()
+    }
+}
@@ -0,0 +1,145 @@
+use super::*;
+use crate::collections::RingBuffer;
+use crate::iter::Iterator;
+#[cfg(feature = "std")]
+use crate::iter::TryIterator;
+#[cfg(feature = "std")]
+use std::string::String;
+
+#[cfg(feature = "std")]
+#[test]
+fn io_lines_reusing() {
+    let input = "a\r\nbb\n\nccc";
+    let res = input
+        .as_bytes()
+        .lines_reusing()
+        .touch(|line| line.push('!'))
+        .filter(|line| line.len() > 2)
+        .fold(String::new(), |mut acc, line| {
+            acc.push_str(line);
+            acc
+        })
+        .unwrap();
+    assert_eq!(res, "bb!ccc!");
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn io_split_reusing() {
+    let mut records = (&b"1,22,,333"[..]).split_reusing(b',');
+    assert_eq!(records.try_next().unwrap(), Some(&b"1"[..]));
+    let count = records.fold(0, |acc, record| acc + record.len()).unwrap();
+    assert_eq!(count, 5);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn io_read_chunks() {
+    let mut chunks = (&b"abcdefg"[..]).read_chunks(3).into_results();
+    assert_eq!(chunks.next().unwrap().unwrap(), b"abc");
+    assert_eq!(chunks.next().unwrap().unwrap(), b"def");
+    assert_eq!(chunks.next().unwrap().unwrap(), b"g");
+    assert!(chunks.next().is_none());
+}
+
+/// Reads one byte at a time, failing once when it reaches `fail`
+#[cfg(feature = "std")]
+struct Flaky {
+    data: &'static [u8],
+    pos: usize,
+    fail: Option<usize>,
+}
+
+#[cfg(feature = "std")]
+impl std::io::Read for Flaky {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.fail == Some(self.pos) {
+            self.fail = None;
+            return Err(std::io::ErrorKind::Other.into());
+        }
+        match (self.data.get(self.pos), buf.first_mut()) {
+            (Some(&b), Some(out)) => {
+                *out = b;
+                self.pos += 1;
+                Ok(1)
+            }
+            _ => Ok(0),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn io_read_chunks_error() {
+    let reader = Flaky {
+        data: b"abcde",
+        pos: 0,
+        fail: Some(4),
+    };
+    let mut chunks = reader.read_chunks(3).into_results();
+    assert_eq!(chunks.next().unwrap().unwrap(), b"abc");
+    assert_eq!(chunks.next().unwrap().unwrap(), b"d");
+    assert!(chunks.next().unwrap().is_err());
+    assert_eq!(chunks.next().unwrap().unwrap(), b"e");
+    assert!(chunks.next().is_none());
+}
+
+#[test]
+fn lending_read_delimited() {
+    let mut frames = (&b"ab\ncde\n\nf"[..]).delimited(b'\n');
+    assert_eq!(&*frames.next().unwrap().unwrap(), b"ab");
+    assert_eq!(&*frames.next().unwrap().unwrap(), b"cde");
+    assert_eq!(&*frames.next().unwrap().unwrap(), b"");
+    assert!(frames.next().is_none());
+    assert_eq!(frames.into_inner(), b"f");
+}
+
+#[test]
+fn lending_read_ring() {
+    let mut ring = RingBuffer::<u8, 8>::new();
+    for b in [9, 9, 9, 9, 9, 0, 2] {
+        ring.push_back(b).unwrap();
+    }
+    ring.consume(5);
+    for b in [b'h', b'i', 0, 1] {
+        ring.push_back(b).unwrap();
+    }
+
+    let mut frames = ring.length_prefixed(2);
+    assert_eq!(&*frames.next().unwrap().unwrap(), b"hi");
+    assert!(frames.next().is_none());
+
+    frames.get_mut().push_back(b'!').unwrap();
+    assert_eq!(frames.get_ref().len(), 3);
+    assert_eq!(&*frames.next().unwrap().unwrap(), b"!");
+    assert!(frames.next().is_none());
+    assert!(frames.into_inner().is_empty());
+}
+
+#[test]
+fn lending_read_truncated() {
+    let mut ring = RingBuffer::<u8, 4>::new();
+    let mut frames = (&mut ring).delimited(b'\n');
+    for b in *b"abcd" {
+        frames.get_mut().push_back(b).unwrap();
+    }
+    assert_eq!(&*frames.next().unwrap().unwrap(), b"abcd");
+    for b in *b"e\ngh" {
+        frames.get_mut().push_back(b).unwrap();
+    }
+    assert!(frames.next().is_none());
+    frames.get_mut().push_back(b'\n').unwrap();
+    assert_eq!(&*frames.next().unwrap().unwrap(), b"gh");
+    assert!(frames.next().is_none());
+
+    let mut frames = ring.length_prefixed(1);
+    for b in [5, b'a', b'b', b'c'] {
+        frames.get_mut().push_back(b).unwrap();
+    }
+    assert_eq!(&*frames.next().unwrap().unwrap(), b"abc");
+    for b in [b'd', b'e', 1, b'f'] {
+        frames.get_mut().push_back(b).unwrap();
+    }
+    assert_eq!(&*frames.next().unwrap().unwrap(), b"f");
+    assert!(frames.into_inner().is_empty());
+}
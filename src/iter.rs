@@ -1,17 +1,41 @@
 //! GAT equivalent of `std` iterator traits, often referred to as a lending iterator
 
 mod adapters;
+mod cursor;
+mod sink;
+mod try_iter;
 
 pub use adapters::*;
+pub use cursor::*;
+pub use sink::*;
+pub use try_iter::*;
+
+use crate::convert::IntoView;
+use crate::ops::FnMutLending;
+
+/// A family of values borrowing for some lifetime, such as the items of a lending iterator. Other
+/// lending traits implement this for a marker type, so they can share [`change_lifetime`].
+pub(crate) trait Lend {
+    /// The value borrowing for `'a`
+    type Lent<'a>
+    where
+        Self: 'a;
+}
+
+impl<I: ?Sized + Iterator> Lend for I {
+    type Lent<'a> = I::Item<'a>
+    where
+        Self: 'a;
+}
 
 /// # Safety:
 /// This is only safe to use if the item provided is sound to have a lifetime of `'b`.
 ///
 /// This is true in cases such as the polonius borrow case and when the user is sure the value can
 /// actually live for the desired time.
-unsafe fn change_lifetime<'a, 'b, I: ?Sized + Iterator>(i: I::Item<'a>) -> I::Item<'b> {
+pub(crate) unsafe fn change_lifetime<'a, 'b, L: ?Sized + Lend>(i: L::Lent<'a>) -> L::Lent<'b> {
     // SAFETY: This functions preconditions assure this is sound
-    unsafe { core::mem::transmute::<I::Item<'a>, I::Item<'b>>(i) }
+    unsafe { core::mem::transmute::<L::Lent<'a>, L::Lent<'b>>(i) }
 }
 
 /// A lending iterator, whose items may have their lifetimes tied to the individual borrow of the
@@ -72,6 +96,44 @@ pub trait Iterator {
         Map::new(self, f)
     }
 
+    /// Take a function which will take each value from the iterator, and yield a new value
+    /// computed from it.
+    ///
+    /// Unlike [`Iterator::map`], the result may borrow from the provided data, so this returns
+    /// a lending iterator
+    fn map_lending<F>(self, f: F) -> MapLending<Self, F>
+    where
+        Self: Sized,
+        F: FnMutLending<Self>,
+    {
+        MapLending::new(self, f)
+    }
+
+    /// Take a function which will take each value from the iterator, and yield the value it
+    /// returns in a `Some`, skipping any `None`s.
+    ///
+    /// As with [`Iterator::map_lending`], the result may borrow from the provided data
+    fn filter_map_lending<F>(self, f: F) -> FilterMapLending<Self, F>
+    where
+        Self: Sized,
+        F: FnMutLending<Self>,
+        for<'a> F::Output<'a>: FilterOutput,
+    {
+        FilterMapLending::new(self, f)
+    }
+
+    /// Convert each value in this iterator into a view of `U`, such as lending a
+    /// [`DequeRangeMut`](crate::collections::DequeRangeMut) for each `&mut RingBuffer`. The views
+    /// may borrow from the values, so this returns a lending iterator
+    fn views<U>(self) -> Views<Self, U>
+    where
+        Self: Sized,
+        U: ?Sized,
+        for<'a> Self::Item<'a>: IntoView<U>,
+    {
+        Views::new(self)
+    }
+
     /// Gain mutable access to each value in this iterator, then yield it to the next step.
     /// This allows altering each item without consuming it, preserving the lending nature
     /// or the iterator
@@ -122,6 +184,17 @@ pub trait Iterator {
         Zip::new(self, other.into_iter())
     }
 
+    /// Pairs each item of this iterator with a slot lent by a [`LendingSink`], so items can be
+    /// written into the sink in place. Stops when either the iterator or the sink runs out. The
+    /// sink is checked with [`LendingSink::is_full`] first, so no item is pulled without a slot.
+    fn zip_sink<S>(self, sink: S) -> ZipSink<Self, S>
+    where
+        Self: Sized,
+        S: LendingSink,
+    {
+        ZipSink::new(self, sink)
+    }
+
     /// Creates an iterator which gives the current iteration count as well as the next value
     fn enumerate(self) -> Enumerate<Self>
     where
@@ -255,6 +328,12 @@ pub trait Iterator {
     }
 }
 
+/// A lending iterator which can also yield items from its back
+pub trait DoubleEndedIterator: Iterator {
+    /// Get the next value from the back of this iterator, or return `None`
+    fn next_back(&mut self) -> Option<Self::Item<'_>>;
+}
+
 /// Trait for values which can be converted into an [`Iterator`]
 pub trait IntoIterator {
     /// The type of the returned iterator
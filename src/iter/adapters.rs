@@ -1,4 +1,8 @@
-use super::{change_lifetime, Iterator};
+use core::marker::PhantomData;
+
+use super::{change_lifetime, DoubleEndedIterator, Iterator};
+use crate::convert::IntoView;
+use crate::ops::FnMutLending;
 
 /// See [`IntoLending::into_lending`]
 pub struct FromCore<I>(pub(crate) I);
@@ -16,6 +20,15 @@ where
     }
 }
 
+impl<I> DoubleEndedIterator for FromCore<I>
+where
+    I: core::iter::DoubleEndedIterator,
+{
+    fn next_back(&mut self) -> Option<Self::Item<'_>> {
+        self.0.next_back()
+    }
+}
+
 /// See [`Iterator::map`]
 pub struct Map<I, F> {
     iter: I,
@@ -40,6 +53,136 @@ where
     }
 }
 
+/// See [`Iterator::map_lending`]
+pub struct MapLending<I, F> {
+    iter: I,
+    func: F,
+}
+
+impl<I, F> MapLending<I, F> {
+    pub(crate) fn new(iter: I, func: F) -> MapLending<I, F> {
+        MapLending { iter, func }
+    }
+}
+
+impl<I, F> Iterator for MapLending<I, F>
+where
+    I: Iterator,
+    F: FnMutLending<I>,
+{
+    type Item<'a> = F::Output<'a>
+    where
+        Self: 'a;
+
+    fn next(&mut self) -> Option<Self::Item<'_>> {
+        Some(self.func.call_mut(self.iter.next()?))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// The output of a function passed to [`Iterator::filter_map_lending`]: an [`Option`] of the
+/// value to yield
+pub trait FilterOutput {
+    /// The value to yield
+    type Value;
+
+    /// Get the value to yield, or `None` to skip it
+    fn into_option(self) -> Option<Self::Value>;
+}
+
+impl<T> FilterOutput for Option<T> {
+    type Value = T;
+
+    fn into_option(self) -> Option<Self::Value> {
+        self
+    }
+}
+
+/// See [`Iterator::filter_map_lending`]
+pub struct FilterMapLending<I, F> {
+    iter: I,
+    func: F,
+}
+
+impl<I, F> FilterMapLending<I, F> {
+    pub(crate) fn new(iter: I, func: F) -> FilterMapLending<I, F> {
+        FilterMapLending { iter, func }
+    }
+}
+
+impl<I, F> Iterator for FilterMapLending<I, F>
+where
+    I: Iterator,
+    F: FnMutLending<I>,
+    for<'a> F::Output<'a>: FilterOutput,
+{
+    type Item<'a> = <F::Output<'a> as FilterOutput>::Value
+    where
+        Self: 'a;
+
+    fn next(&mut self) -> Option<Self::Item<'_>> {
+        while let Some(val) = self.iter.next() {
+            if let Some(out) = self.func.call_mut(val).into_option() {
+                // SAFETY: This is the polonius case
+                return Some(unsafe { change_lifetime::<Self>(out) });
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
+
+/// See [`Iterator::views`]
+pub struct Views<I, U: ?Sized> {
+    iter: I,
+    _view: PhantomData<fn() -> *const U>,
+}
+
+impl<I, U: ?Sized> Views<I, U> {
+    pub(crate) fn new(iter: I) -> Views<I, U> {
+        Views {
+            iter,
+            _view: PhantomData,
+        }
+    }
+}
+
+impl<I, U> Iterator for Views<I, U>
+where
+    I: Iterator,
+    U: ?Sized,
+    for<'a> I::Item<'a>: IntoView<U>,
+{
+    type Item<'a> = <I::Item<'a> as IntoView<U>>::View
+    where
+        Self: 'a;
+
+    fn next(&mut self) -> Option<Self::Item<'_>> {
+        self.iter.next().map(IntoView::into_view)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I, U> DoubleEndedIterator for Views<I, U>
+where
+    I: DoubleEndedIterator,
+    U: ?Sized,
+    for<'a> I::Item<'a>: IntoView<U>,
+{
+    fn next_back(&mut self) -> Option<Self::Item<'_>> {
+        self.iter.next_back().map(IntoView::into_view)
+    }
+}
+
 /// See [`Iterator::touch`]
 pub struct Touch<I, F> {
     iter: I,
@@ -130,7 +273,7 @@ where
             self.first = false;
             self.iter.next()
         } else {
-            self.nth(self.step - 1)
+            self.iter.nth(self.step - 1)
         }
     }
 }
@@ -0,0 +1,392 @@
+#[cfg(feature = "alloc")]
+use alloc::collections::{LinkedList, VecDeque};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use super::{DoubleEndedIterator, Iterator};
+
+/// A cursor over a sequence, which can move in both directions and lend its current element.
+///
+/// Like the unstable `std` linked list cursors, a cursor has a 'ghost' position between the last
+/// and first elements, where there is no current element. Moving forward from the ghost goes to
+/// the first element, and moving backward from it goes to the last.
+pub trait Cursor {
+    /// The value lent by [`Cursor::current`]
+    type Item<'a>
+    where
+        Self: 'a;
+
+    /// The value lent by [`Cursor::current_mut`]
+    type ItemMut<'a>
+    where
+        Self: 'a;
+
+    /// Get the current element, or `None` if at the ghost position
+    fn current(&self) -> Option<Self::Item<'_>>;
+
+    /// Get the current element mutably, or `None` if at the ghost position
+    fn current_mut(&mut self) -> Option<Self::ItemMut<'_>>;
+
+    /// Move to the next element
+    fn move_next(&mut self);
+
+    /// Move to the previous element
+    fn move_prev(&mut self);
+
+    /// Get the index of the current element, or `None` if at the ghost position
+    fn index(&self) -> Option<usize>;
+
+    /// Get the number of elements in the underlying sequence
+    fn len(&self) -> usize;
+
+    /// Check whether the underlying sequence contains no elements
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Move to the element at `idx`, or to the ghost position if `idx` is out of bounds. By
+    /// default, this moves one element at a time.
+    fn seek(&mut self, idx: usize) {
+        let len = self.len();
+        let idx = idx.min(len);
+        let mut cur = self.index().unwrap_or(len);
+        while cur < idx {
+            self.move_next();
+            cur += 1;
+        }
+        while cur > idx {
+            self.move_prev();
+            cur -= 1;
+        }
+    }
+
+    /// Convert this cursor into a lending iterator over all its elements, from the first. The
+    /// iterator is double-ended, but alternating between the ends may be slow on cursors which
+    /// can't seek quickly.
+    fn into_lending_iter(self) -> CursorIter<Self>
+    where
+        Self: Sized,
+    {
+        CursorIter::new(self)
+    }
+}
+
+impl<C: ?Sized + Cursor> Cursor for &mut C {
+    type Item<'a> = C::Item<'a>
+    where
+        Self: 'a;
+
+    type ItemMut<'a> = C::ItemMut<'a>
+    where
+        Self: 'a;
+
+    fn current(&self) -> Option<Self::Item<'_>> {
+        (**self).current()
+    }
+
+    fn current_mut(&mut self) -> Option<Self::ItemMut<'_>> {
+        (**self).current_mut()
+    }
+
+    fn move_next(&mut self) {
+        (**self).move_next()
+    }
+
+    fn move_prev(&mut self) {
+        (**self).move_prev()
+    }
+
+    fn index(&self) -> Option<usize> {
+        (**self).index()
+    }
+
+    fn len(&self) -> usize {
+        (**self).len()
+    }
+
+    fn seek(&mut self, idx: usize) {
+        (**self).seek(idx)
+    }
+}
+
+/// Trait for values which can be converted into a [`Cursor`], starting at their first element
+pub trait IntoCursor {
+    /// The type of the returned cursor
+    type Cursor: Cursor;
+
+    /// Convert this value into a [`Cursor`]
+    fn into_cursor(self) -> Self::Cursor;
+}
+
+/// A cursor over a slice, or anything which dereferences to one
+pub struct SliceCursor<'a, T> {
+    slice: &'a mut [T],
+    pos: usize,
+}
+
+impl<T> Cursor for SliceCursor<'_, T> {
+    type Item<'a> = &'a T
+    where
+        Self: 'a;
+
+    type ItemMut<'a> = &'a mut T
+    where
+        Self: 'a;
+
+    fn current(&self) -> Option<Self::Item<'_>> {
+        self.slice.get(self.pos)
+    }
+
+    fn current_mut(&mut self) -> Option<Self::ItemMut<'_>> {
+        self.slice.get_mut(self.pos)
+    }
+
+    fn move_next(&mut self) {
+        self.pos = if self.pos < self.slice.len() {
+            self.pos + 1
+        } else {
+            0
+        };
+    }
+
+    fn move_prev(&mut self) {
+        self.pos = if self.pos > 0 {
+            self.pos - 1
+        } else {
+            self.slice.len()
+        };
+    }
+
+    fn index(&self) -> Option<usize> {
+        (self.pos < self.slice.len()).then_some(self.pos)
+    }
+
+    fn len(&self) -> usize {
+        self.slice.len()
+    }
+
+    fn seek(&mut self, idx: usize) {
+        self.pos = idx.min(self.slice.len());
+    }
+}
+
+impl<'a, T> IntoCursor for &'a mut [T] {
+    type Cursor = SliceCursor<'a, T>;
+
+    fn into_cursor(self) -> Self::Cursor {
+        SliceCursor {
+            slice: self,
+            pos: 0,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T> IntoCursor for &'a mut Vec<T> {
+    type Cursor = SliceCursor<'a, T>;
+
+    fn into_cursor(self) -> Self::Cursor {
+        self.as_mut_slice().into_cursor()
+    }
+}
+
+/// A cursor over a [`VecDeque`]
+#[cfg(feature = "alloc")]
+pub struct DequeCursor<'a, T> {
+    deque: &'a mut VecDeque<T>,
+    pos: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl<T> Cursor for DequeCursor<'_, T> {
+    type Item<'a> = &'a T
+    where
+        Self: 'a;
+
+    type ItemMut<'a> = &'a mut T
+    where
+        Self: 'a;
+
+    fn current(&self) -> Option<Self::Item<'_>> {
+        self.deque.get(self.pos)
+    }
+
+    fn current_mut(&mut self) -> Option<Self::ItemMut<'_>> {
+        self.deque.get_mut(self.pos)
+    }
+
+    fn move_next(&mut self) {
+        self.pos = if self.pos < self.deque.len() {
+            self.pos + 1
+        } else {
+            0
+        };
+    }
+
+    fn move_prev(&mut self) {
+        self.pos = if self.pos > 0 {
+            self.pos - 1
+        } else {
+            self.deque.len()
+        };
+    }
+
+    fn index(&self) -> Option<usize> {
+        (self.pos < self.deque.len()).then_some(self.pos)
+    }
+
+    fn len(&self) -> usize {
+        self.deque.len()
+    }
+
+    fn seek(&mut self, idx: usize) {
+        self.pos = idx.min(self.deque.len());
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T> IntoCursor for &'a mut VecDeque<T> {
+    type Cursor = DequeCursor<'a, T>;
+
+    fn into_cursor(self) -> Self::Cursor {
+        DequeCursor {
+            deque: self,
+            pos: 0,
+        }
+    }
+}
+
+/// A cursor over a [`LinkedList`]. Moving by one element is O(1), and doesn't allocate.
+///
+/// While the cursor is alive, the list is split in two at the current element, and appears empty.
+/// The list is restored when the cursor is dropped.
+#[cfg(feature = "alloc")]
+pub struct ListCursor<'a, T> {
+    list: &'a mut LinkedList<T>,
+    front: LinkedList<T>,
+    back: LinkedList<T>,
+}
+
+#[cfg(feature = "alloc")]
+impl<T> Drop for ListCursor<'_, T> {
+    fn drop(&mut self) {
+        self.front.append(&mut self.back);
+        core::mem::swap(self.list, &mut self.front);
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> Cursor for ListCursor<'_, T> {
+    type Item<'a> = &'a T
+    where
+        Self: 'a;
+
+    type ItemMut<'a> = &'a mut T
+    where
+        Self: 'a;
+
+    fn current(&self) -> Option<Self::Item<'_>> {
+        self.back.front()
+    }
+
+    fn current_mut(&mut self) -> Option<Self::ItemMut<'_>> {
+        self.back.front_mut()
+    }
+
+    fn move_next(&mut self) {
+        if self.back.is_empty() {
+            core::mem::swap(&mut self.front, &mut self.back);
+        } else {
+            // Splitting and appending at the ends of the lists moves a single node
+            let rest = self.back.split_off(1);
+            self.front.append(&mut self.back);
+            self.back = rest;
+        }
+    }
+
+    fn move_prev(&mut self) {
+        if self.front.is_empty() {
+            core::mem::swap(&mut self.front, &mut self.back);
+        } else {
+            let mut last = self.front.split_off(self.front.len() - 1);
+            last.append(&mut self.back);
+            self.back = last;
+        }
+    }
+
+    fn index(&self) -> Option<usize> {
+        (!self.back.is_empty()).then_some(self.front.len())
+    }
+
+    fn len(&self) -> usize {
+        self.front.len() + self.back.len()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T> IntoCursor for &'a mut LinkedList<T> {
+    type Cursor = ListCursor<'a, T>;
+
+    fn into_cursor(self) -> Self::Cursor {
+        let back = core::mem::take(self);
+        ListCursor {
+            list: self,
+            front: LinkedList::new(),
+            back,
+        }
+    }
+}
+
+/// See [`Cursor::into_lending_iter`]
+pub struct CursorIter<C> {
+    cursor: C,
+    front: usize,
+    back: usize,
+}
+
+impl<C: Cursor> CursorIter<C> {
+    pub(crate) fn new(cursor: C) -> CursorIter<C> {
+        let back = cursor.len();
+        CursorIter {
+            cursor,
+            front: 0,
+            back,
+        }
+    }
+
+    /// Get the underlying cursor, at the position of the last lent element
+    pub fn into_cursor(self) -> C {
+        self.cursor
+    }
+}
+
+impl<C: Cursor> Iterator for CursorIter<C> {
+    type Item<'a> = C::ItemMut<'a>
+    where
+        Self: 'a;
+
+    fn next(&mut self) -> Option<Self::Item<'_>> {
+        if self.front == self.back {
+            return None;
+        }
+        self.cursor.seek(self.front);
+        self.front += 1;
+        self.cursor.current_mut()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl<C: Cursor> DoubleEndedIterator for CursorIter<C> {
+    fn next_back(&mut self) -> Option<Self::Item<'_>> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        self.cursor.seek(self.back);
+        self.cursor.current_mut()
+    }
+}
@@ -0,0 +1,289 @@
+use core::mem::MaybeUninit;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use super::Iterator;
+use crate::collections::RingBuffer;
+
+/// A lending sink, the mirror image of a lending iterator. Instead of lending out items, this
+/// lends out slots to be filled in place, which may be plain mutable references, uninitialized
+/// memory, or guards that commit the value once written.
+///
+/// Slices can be used as sinks through `&mut [T]`, and fixed arrays through [`ArraySink`].
+pub trait LendingSink {
+    /// The slot lent by each call to `next_slot` on this sink
+    type Slot<'a>
+    where
+        Self: 'a;
+
+    /// Get the next slot of this sink, or return `None` if it is full
+    fn next_slot(&mut self) -> Option<Self::Slot<'_>>;
+
+    /// Check whether this sink is full, so the next call to `next_slot` would return `None`.
+    /// Sinks that can fill up should override this, so adapters such as [`Iterator::zip_sink`]
+    /// don't pull an item with nowhere to put it.
+    fn is_full(&self) -> bool {
+        false
+    }
+
+    /// Get the bounds on the number of slots left in this sink, like [`Iterator::size_hint`].
+    /// The default of `(0, None)` is correct for any sink.
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, None)
+    }
+}
+
+/// A slot which can have a value of type `T` placed into it
+pub trait Emplace<T> {
+    /// Place `val` into this slot
+    fn emplace(self, val: T);
+}
+
+impl<T> Emplace<T> for &mut T {
+    fn emplace(self, val: T) {
+        *self = val;
+    }
+}
+
+impl<T> Emplace<T> for &mut MaybeUninit<T> {
+    fn emplace(self, val: T) {
+        self.write(val);
+    }
+}
+
+impl<S: ?Sized + LendingSink> LendingSink for &mut S {
+    type Slot<'a> = S::Slot<'a>
+    where
+        Self: 'a;
+
+    fn next_slot(&mut self) -> Option<Self::Slot<'_>> {
+        (**self).next_slot()
+    }
+
+    fn is_full(&self) -> bool {
+        (**self).is_full()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (**self).size_hint()
+    }
+}
+
+impl<T> LendingSink for &mut [T] {
+    type Slot<'a> = &'a mut T
+    where
+        Self: 'a;
+
+    fn next_slot(&mut self) -> Option<Self::Slot<'_>> {
+        let (first, rest) = core::mem::take(self).split_first_mut()?;
+        *self = rest;
+        Some(first)
+    }
+
+    fn is_full(&self) -> bool {
+        self.is_empty()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len(), Some(self.len()))
+    }
+}
+
+/// A sink over a fixed array, which overwrites each element in order
+pub struct ArraySink<T, const N: usize> {
+    array: [T; N],
+    len: usize,
+}
+
+impl<T, const N: usize> ArraySink<T, N> {
+    /// Create a new sink which will overwrite the elements of `array`, starting from the first
+    pub fn new(array: [T; N]) -> ArraySink<T, N> {
+        ArraySink { array, len: 0 }
+    }
+
+    /// Get the elements which have been lent out so far
+    pub fn filled(&self) -> &[T] {
+        &self.array[..self.len]
+    }
+
+    /// Get the underlying array
+    pub fn into_inner(self) -> [T; N] {
+        self.array
+    }
+}
+
+impl<T, const N: usize> From<[T; N]> for ArraySink<T, N> {
+    fn from(array: [T; N]) -> Self {
+        ArraySink::new(array)
+    }
+}
+
+impl<T, const N: usize> LendingSink for ArraySink<T, N> {
+    type Slot<'a> = &'a mut T
+    where
+        Self: 'a;
+
+    fn next_slot(&mut self) -> Option<Self::Slot<'_>> {
+        let slot = self.array.get_mut(self.len)?;
+        self.len += 1;
+        Some(slot)
+    }
+
+    fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (N - self.len, Some(N - self.len))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> LendingSink for Vec<T> {
+    type Slot<'a> = VecSlot<'a, T>
+    where
+        Self: 'a;
+
+    fn next_slot(&mut self) -> Option<Self::Slot<'_>> {
+        Some(VecSlot { vec: self })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+/// The slot lent by a `Vec` sink, which pushes a value onto the end of the vector once written.
+/// Dropping the slot without writing to it leaves the vector unchanged.
+#[cfg(feature = "alloc")]
+pub struct VecSlot<'a, T> {
+    vec: &'a mut Vec<T>,
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T> VecSlot<'a, T> {
+    /// Push `val` onto the vector, returning a reference to it
+    pub fn insert(self, val: T) -> &'a mut T {
+        let idx = self.vec.len();
+        self.vec.push(val);
+        &mut self.vec[idx]
+    }
+
+    /// Get the uninitialized memory for this slot, to be constructed in place
+    pub fn as_uninit(&mut self) -> &mut MaybeUninit<T> {
+        self.vec.reserve(1);
+        &mut self.vec.spare_capacity_mut()[0]
+    }
+
+    /// Commit the value constructed in place, returning a reference to it
+    ///
+    /// # Safety
+    ///
+    /// The memory from [`VecSlot::as_uninit`] must have been initialized
+    pub unsafe fn assume_init(self) -> &'a mut T {
+        let idx = self.vec.len();
+        // SAFETY: Our caller guarantees the memory past the end of the vector was reserved and
+        //         initialized through `as_uninit`
+        unsafe { self.vec.set_len(idx + 1) };
+        &mut self.vec[idx]
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> Emplace<T> for VecSlot<'_, T> {
+    fn emplace(self, val: T) {
+        self.insert(val);
+    }
+}
+
+impl<T, const N: usize> LendingSink for RingBuffer<T, N> {
+    type Slot<'a> = RingSlot<'a, T, N>
+    where
+        Self: 'a;
+
+    fn next_slot(&mut self) -> Option<Self::Slot<'_>> {
+        if self.is_full() {
+            None
+        } else {
+            Some(RingSlot { ring: self })
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        RingBuffer::is_full(self)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (N - self.len(), Some(N - self.len()))
+    }
+}
+
+/// The slot lent by a [`RingBuffer`] sink, which pushes a value onto the back of the buffer once
+/// written. Dropping the slot without writing to it leaves the buffer unchanged.
+pub struct RingSlot<'a, T, const N: usize> {
+    ring: &'a mut RingBuffer<T, N>,
+}
+
+impl<'a, T, const N: usize> RingSlot<'a, T, N> {
+    /// Push `val` onto the back of the buffer, returning a reference to it
+    pub fn insert(self, val: T) -> &'a mut T {
+        if self.ring.push_back(val).is_err() {
+            unreachable!("ring buffer slots are only lent while the buffer has space");
+        }
+        let idx = self.ring.len() - 1;
+        &mut self.ring[idx]
+    }
+}
+
+impl<T, const N: usize> Emplace<T> for RingSlot<'_, T, N> {
+    fn emplace(self, val: T) {
+        self.insert(val);
+    }
+}
+
+/// See [`Iterator::zip_sink`]
+pub struct ZipSink<I, S> {
+    iter: I,
+    sink: S,
+}
+
+impl<I, S> ZipSink<I, S> {
+    pub(crate) fn new(iter: I, sink: S) -> ZipSink<I, S> {
+        ZipSink { iter, sink }
+    }
+
+    /// Get the underlying sink
+    pub fn into_sink(self) -> S {
+        self.sink
+    }
+}
+
+impl<I, S> Iterator for ZipSink<I, S>
+where
+    I: Iterator,
+    S: LendingSink,
+{
+    type Item<'a> = (I::Item<'a>, S::Slot<'a>)
+    where
+        Self: 'a;
+
+    fn next(&mut self) -> Option<Self::Item<'_>> {
+        if self.sink.is_full() {
+            return None;
+        }
+        let item = self.iter.next()?;
+        let slot = self.sink.next_slot()?;
+        Some((item, slot))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.iter.size_hint();
+        let (slots_lower, slots_upper) = self.sink.size_hint();
+        let upper = match (upper, slots_upper) {
+            (Some(upper), Some(slots)) => Some(upper.min(slots)),
+            (upper, None) | (None, upper) => upper,
+        };
+        (lower.min(slots_lower), upper)
+    }
+}
@@ -49,3 +49,267 @@ fn iter_chain() {
 
     assert_eq!(res, 20);
 }
+
+struct PairIter([(u8, u8); 3], usize);
+
+impl Iterator for PairIter {
+    type Item<'a> = &'a mut (u8, u8);
+
+    fn next(&mut self) -> Option<Self::Item<'_>> {
+        let out = self.0.get_mut(self.1)?;
+        self.1 += 1;
+        Some(out)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.0.len() - self.1;
+        (len, Some(len))
+    }
+}
+
+#[test]
+fn iter_map_lending() {
+    let iter = PairIter([(1, 2), (3, 4), (5, 6)], 0);
+
+    let res = iter
+        .map_lending(crate::lending!(|pair: &mut (u8, u8)| &pair.1))
+        .fold(0, |acc, val| acc + *val);
+
+    assert_eq!(res, 12);
+}
+
+#[test]
+fn iter_map_lending_size_hint() {
+    let mut iter = PairIter([(1, 2), (3, 4), (5, 6)], 0)
+        .map_lending(crate::lending!(|pair: &mut (u8, u8)| &pair.1));
+
+    assert_eq!(iter.size_hint(), (3, Some(3)));
+    iter.next();
+    assert_eq!(iter.size_hint(), (2, Some(2)));
+}
+
+/// Lends overlapping windows of its buffer
+struct WindowIter([u8; 4], usize);
+
+impl Iterator for WindowIter {
+    type Item<'a> = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item<'_>> {
+        let out = self.0.get(self.1..self.1 + 2)?;
+        self.1 += 1;
+        Some(out)
+    }
+}
+
+#[test]
+fn iter_map_lending_shared() {
+    let iter = WindowIter([1, 2, 3, 4], 0);
+
+    let res = iter
+        .map_lending(crate::lending!(|window: &[u8]| &window[1]))
+        .fold(0, |acc, val| acc + *val);
+
+    assert_eq!(res, 9);
+}
+
+#[test]
+fn iter_filter_map_lending() {
+    let iter = PairIter([(1, 2), (3, 4), (5, 6)], 0).filter_map_lending(crate::lending!(
+        |pair: &mut (u8, u8)| -> Option {
+            pair.1 += 1;
+            (pair.0 != 3).then_some(&pair.1)
+        }
+    ));
+
+    assert_eq!(iter.size_hint(), (0, Some(3)));
+    let res = iter.fold(0, |acc, val| acc + *val);
+
+    assert_eq!(res, 10);
+}
+
+/// Lends its buffer filled with each value, failing once it reaches `fail`
+struct TryLendingIter {
+    buf: [u8; 2],
+    next: u8,
+    fail: u8,
+}
+
+impl TryLendingIter {
+    fn new(fail: u8) -> TryLendingIter {
+        TryLendingIter {
+            buf: [0; 2],
+            next: 0,
+            fail,
+        }
+    }
+}
+
+impl TryIterator for TryLendingIter {
+    type Item<'a> = &'a mut [u8; 2];
+    type Error = u8;
+
+    fn try_next(&mut self) -> Result<Option<Self::Item<'_>>, Self::Error> {
+        if self.next == self.fail {
+            return Err(self.next);
+        }
+        self.buf = [self.next; 2];
+        self.next += 1;
+        Ok(Some(&mut self.buf))
+    }
+}
+
+#[test]
+fn try_iter_adapters() {
+    let res = TryLendingIter::new(10)
+        .filter(|buf| buf[0] % 2 == 1)
+        .take(3)
+        .fold(0, |acc, buf| acc + buf[1]);
+    assert_eq!(res, Ok(9));
+
+    assert_eq!(TryLendingIter::new(10).take(3).size_hint(), (0, Some(3)));
+    assert_eq!(TryLendingIter::new(10).take(0).size_hint(), (0, Some(0)));
+
+    let res = TryLendingIter::new(4)
+        .map_err(|err| err * 2)
+        .for_each(|buf| buf[0] += 1);
+    assert_eq!(res, Err(8));
+}
+
+#[test]
+fn try_iter_results() {
+    let mut iter = TryLendingIter::new(1).into_results();
+    assert_eq!(iter.next(), Some(Ok(&mut [0, 0])));
+    assert_eq!(iter.next(), Some(Err(1)));
+
+    let res = [Ok(1), Ok(2), Err("bad"), Ok(3)]
+        .into_iter()
+        .into_lending()
+        .into_try()
+        .fold(0, |acc, val| acc + val);
+    assert_eq!(res, Err("bad"));
+}
+
+#[test]
+fn iter_zip_sink() {
+    let mut out = [0u8; 4];
+    let mut slots = &mut out[..];
+    let mut iter = LendingIter::new().touch(|a| **a += 1).zip_sink(&mut slots);
+    while let Some((item, slot)) = iter.next() {
+        slot.emplace(*item * 2);
+    }
+    assert!(slots.is_empty());
+    assert_eq!(out, [2, 4, 6, 8]);
+
+    let mut ring = crate::collections::RingBuffer::<u8, 3>::new();
+    let mut iter = LendingIter::new().zip_sink(&mut ring);
+    while let Some((item, slot)) = iter.next() {
+        *slot.insert(*item) += 1;
+    }
+    assert_eq!(ring.as_slices(), (&[1, 1, 1][..], &[][..]));
+
+    let mut pulled = 0;
+    let mut iter = LendingIter::new()
+        .touch(|a| {
+            **a += 1;
+            pulled += 1;
+        })
+        .zip_sink(ArraySink::new([0u8; 3]));
+    while let Some((item, slot)) = iter.next() {
+        slot.emplace(*item);
+    }
+    assert!(iter.next().is_none());
+    let sink = iter.into_sink();
+    assert_eq!(sink.filled(), [1, 2, 3]);
+    assert_eq!(pulled, 3);
+}
+
+#[test]
+fn iter_zip_sink_size_hint() {
+    let pairs = || PairIter([(1, 2), (3, 4), (5, 6)], 0);
+    let mut out = [(0, 0); 5];
+    let mut iter = pairs().zip_sink(&mut out[..]);
+    assert_eq!(iter.size_hint(), (3, Some(3)));
+    iter.next();
+    assert_eq!(iter.size_hint(), (2, Some(2)));
+
+    let iter = pairs().zip_sink(ArraySink::new([(0, 0); 2]));
+    assert_eq!(iter.size_hint(), (2, Some(2)));
+
+    let mut ring = crate::collections::RingBuffer::<u8, 4>::new();
+    ring.push_back(0).unwrap();
+    let iter = LendingIter::new().zip_sink(&mut ring);
+    assert_eq!(iter.size_hint(), (0, Some(3)));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn iter_sink_vec() {
+    let mut vec = alloc::vec::Vec::new();
+    vec.next_slot().unwrap().emplace(1);
+    let mut slot = vec.next_slot().unwrap();
+    slot.as_uninit().write(2);
+    // SAFETY: The slot was just initialized
+    let val = unsafe { slot.assume_init() };
+    *val += 1;
+    assert!(vec.next_slot().is_some());
+    assert_eq!(vec, [1, 3]);
+}
+
+#[test]
+fn cursor_slice() {
+    let mut arr = [1, 2, 3];
+    let mut cursor = (&mut arr[..]).into_cursor();
+    assert_eq!(cursor.current(), Some(&1));
+    cursor.move_prev();
+    assert_eq!(cursor.index(), None);
+    cursor.move_prev();
+    *cursor.current_mut().unwrap() += 10;
+    cursor.move_next();
+    cursor.move_next();
+    assert_eq!(cursor.current(), Some(&1));
+
+    let mut iter = cursor.into_lending_iter();
+    assert_eq!(iter.next_back(), Some(&mut 13));
+    assert_eq!(iter.next(), Some(&mut 1));
+    assert_eq!(iter.size_hint(), (1, Some(1)));
+    assert_eq!(iter.next_back(), Some(&mut 2));
+    assert_eq!(iter.next(), None);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn cursor_collections() {
+    use alloc::collections::{LinkedList, VecDeque};
+
+    macro_rules! walk {
+        ($cursor:expr) => {{
+            let mut out = alloc::vec::Vec::new();
+            let mut iter = $cursor.into_lending_iter();
+            while let Some(item) = iter.next_back() {
+                *item *= 2;
+                out.push(*item);
+            }
+            out
+        }};
+    }
+
+    let mut vec = alloc::vec![1, 2, 3];
+    assert_eq!(walk!((&mut vec).into_cursor()), [6, 4, 2]);
+    let mut deque = VecDeque::from([1, 2, 3]);
+    assert_eq!(walk!((&mut deque).into_cursor()), [6, 4, 2]);
+    let mut list = LinkedList::from([1, 2, 3]);
+    assert_eq!(walk!((&mut list).into_cursor()), [6, 4, 2]);
+    assert!(list.iter().eq(&[2, 4, 6]));
+
+    let mut cursor = (&mut list).into_cursor();
+    cursor.move_prev();
+    cursor.move_prev();
+    assert_eq!((cursor.index(), cursor.current()), (Some(2), Some(&6)));
+    cursor.move_next();
+    cursor.move_next();
+    assert_eq!((cursor.index(), cursor.current()), (Some(0), Some(&2)));
+    cursor.seek(1);
+    *cursor.current_mut().unwrap() = 0;
+    drop(cursor);
+    assert!(list.iter().eq(&[2, 0, 6]));
+}
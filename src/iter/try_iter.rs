@@ -0,0 +1,354 @@
+use core::marker::PhantomData;
+
+use super::{change_lifetime, Iterator, Lend};
+
+/// The items of a [`TryIterator`], so they can use [`change_lifetime`]
+struct TryItems<I: ?Sized>(PhantomData<I>);
+
+impl<I: ?Sized + TryIterator> Lend for TryItems<I> {
+    type Lent<'a> = I::Item<'a>
+    where
+        Self: 'a;
+}
+
+/// A fallible lending iterator, for sources such as parsers and readers which may fail
+/// mid-stream. Errors are returned separately from items, so adapters like
+/// [`TryIterator::filter`] see the items directly.
+pub trait TryIterator {
+    /// The value yielded by each successful call to `try_next` on this iterator
+    type Item<'a>
+    where
+        Self: 'a;
+
+    /// The error which may occur while getting the next value
+    type Error;
+
+    /// Get the next value of this iterator, `None` if it is exhausted, or an error
+    fn try_next(&mut self) -> Result<Option<Self::Item<'_>>, Self::Error>;
+
+    /// Get a hint as to the size of this iterator - the first value is a lower bound, second
+    /// is an optional upper bound.
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, None)
+    }
+
+    // Lazy Adaptors
+
+    /// Take a closure which will convert each error of this iterator into a new error
+    fn map_err<E, F>(self, f: F) -> MapErr<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(Self::Error) -> E,
+    {
+        MapErr::new(self, f)
+    }
+
+    /// Gain mutable access to each value in this iterator, then yield it to the next step.
+    /// This allows altering each item without consuming it, preserving the lending nature
+    /// or the iterator
+    fn touch<F>(self, f: F) -> TryTouch<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(&mut Self::Item<'_>),
+    {
+        TryTouch::new(self, f)
+    }
+
+    /// Execute a closure on each item in the iterator, returning true if it should be included, or
+    /// false to skip it. Errors are always passed through.
+    fn filter<F>(self, f: F) -> TryFilter<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(&Self::Item<'_>) -> bool,
+    {
+        TryFilter::new(self, f)
+    }
+
+    /// Creates an iterator that yields the first n elements, or fewer if the underlying iterator
+    /// ends sooner
+    fn take(self, n: usize) -> TryTake<Self>
+    where
+        Self: Sized,
+    {
+        TryTake::new(self, n)
+    }
+
+    /// Convert this into a lending iterator over `Result` items
+    fn into_results(self) -> IntoResults<Self>
+    where
+        Self: Sized,
+    {
+        IntoResults::new(self)
+    }
+
+    // Consumers
+
+    /// Execute a closure on each value of this iterator, stopping at the first error
+    fn for_each<F>(mut self, mut f: F) -> Result<(), Self::Error>
+    where
+        Self: Sized,
+        F: FnMut(Self::Item<'_>),
+    {
+        while let Some(next) = self.try_next()? {
+            f(next)
+        }
+        Ok(())
+    }
+
+    /// Execute a closure on each value of this iterator, with an additional 'accumulator' value
+    /// passed to each call. The closure is expected to return the new value of the accumulator.
+    /// Stops at the first error.
+    fn fold<T, F>(mut self, acc: T, mut f: F) -> Result<T, Self::Error>
+    where
+        Self: Sized,
+        F: FnMut(T, Self::Item<'_>) -> T,
+    {
+        let mut acc = acc;
+        while let Some(x) = self.try_next()? {
+            acc = f(acc, x);
+        }
+        Ok(acc)
+    }
+}
+
+/// Values which can be split into a success or failure, such as [`Result`]. This is used to
+/// convert lending iterators over fallible items into a [`TryIterator`].
+pub trait TryItem {
+    /// The success value
+    type Value;
+    /// The failure value
+    type Error;
+
+    /// Convert this value into a `Result`
+    fn into_result(self) -> Result<Self::Value, Self::Error>;
+}
+
+impl<T, E> TryItem for Result<T, E> {
+    type Value = T;
+    type Error = E;
+
+    fn into_result(self) -> Result<Self::Value, Self::Error> {
+        self
+    }
+}
+
+/// Trait for converting a lending iterator over `Result` items into a [`TryIterator`].
+///
+/// The items must be convertible for every lifetime. As [`Iterator::Item`] requires `Self: 'a`,
+/// the compiler can currently only prove that for `'static` iterators.
+pub trait IntoTry<E>: Sized {
+    /// Convert this iterator into a fallible one
+    fn into_try(self) -> FromResults<Self, E>;
+}
+
+impl<I, E> IntoTry<E> for I
+where
+    I: Iterator,
+    for<'a> I::Item<'a>: TryItem<Error = E>,
+{
+    fn into_try(self) -> FromResults<Self, E> {
+        FromResults {
+            iter: self,
+            _err: PhantomData,
+        }
+    }
+}
+
+/// See [`IntoTry::into_try`]
+pub struct FromResults<I, E> {
+    iter: I,
+    _err: PhantomData<fn() -> E>,
+}
+
+impl<I, E> TryIterator for FromResults<I, E>
+where
+    I: Iterator,
+    for<'a> I::Item<'a>: TryItem<Error = E>,
+{
+    type Item<'a> = <I::Item<'a> as TryItem>::Value
+    where
+        Self: 'a;
+
+    type Error = E;
+
+    fn try_next(&mut self) -> Result<Option<Self::Item<'_>>, Self::Error> {
+        self.iter.next().map(TryItem::into_result).transpose()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// See [`TryIterator::into_results`]
+pub struct IntoResults<I> {
+    iter: I,
+}
+
+impl<I> IntoResults<I> {
+    pub(crate) fn new(iter: I) -> IntoResults<I> {
+        IntoResults { iter }
+    }
+}
+
+impl<I> Iterator for IntoResults<I>
+where
+    I: TryIterator,
+{
+    type Item<'a> = Result<I::Item<'a>, I::Error>
+    where
+        Self: 'a;
+
+    fn next(&mut self) -> Option<Self::Item<'_>> {
+        self.iter.try_next().transpose()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// See [`TryIterator::map_err`]
+pub struct MapErr<I, F> {
+    iter: I,
+    func: F,
+}
+
+impl<I, F> MapErr<I, F> {
+    pub(crate) fn new(iter: I, func: F) -> MapErr<I, F> {
+        MapErr { iter, func }
+    }
+}
+
+impl<I, F, E> TryIterator for MapErr<I, F>
+where
+    I: TryIterator,
+    F: FnMut(I::Error) -> E,
+{
+    type Item<'a> = I::Item<'a>
+    where
+        Self: 'a;
+
+    type Error = E;
+
+    fn try_next(&mut self) -> Result<Option<Self::Item<'_>>, Self::Error> {
+        self.iter.try_next().map_err(&mut self.func)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// See [`TryIterator::touch`]
+pub struct TryTouch<I, F> {
+    iter: I,
+    func: F,
+}
+
+impl<I, F> TryTouch<I, F> {
+    pub(crate) fn new(iter: I, func: F) -> TryTouch<I, F> {
+        TryTouch { iter, func }
+    }
+}
+
+impl<I, F> TryIterator for TryTouch<I, F>
+where
+    I: TryIterator,
+    F: FnMut(&mut I::Item<'_>),
+{
+    type Item<'a> = I::Item<'a>
+    where
+        Self: 'a;
+
+    type Error = I::Error;
+
+    fn try_next(&mut self) -> Result<Option<Self::Item<'_>>, Self::Error> {
+        let mut out = match self.iter.try_next()? {
+            Some(out) => out,
+            None => return Ok(None),
+        };
+        (self.func)(&mut out);
+        Ok(Some(out))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// See [`TryIterator::filter`]
+pub struct TryFilter<I, F> {
+    iter: I,
+    func: F,
+}
+
+impl<I, F> TryFilter<I, F> {
+    pub(crate) fn new(iter: I, func: F) -> TryFilter<I, F> {
+        TryFilter { iter, func }
+    }
+}
+
+impl<I, F> TryIterator for TryFilter<I, F>
+where
+    I: TryIterator,
+    F: FnMut(&I::Item<'_>) -> bool,
+{
+    type Item<'a> = I::Item<'a>
+    where
+        Self: 'a;
+
+    type Error = I::Error;
+
+    fn try_next(&mut self) -> Result<Option<Self::Item<'_>>, Self::Error> {
+        while let Some(val) = self.iter.try_next()? {
+            if (self.func)(&val) {
+                // SAFETY: This is the polonius case
+                return Ok(Some(unsafe { change_lifetime::<TryItems<I>>(val) }));
+            }
+        }
+        Ok(None)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
+
+/// See [`TryIterator::take`]
+pub struct TryTake<I> {
+    iter: I,
+    take: usize,
+}
+
+impl<I> TryTake<I> {
+    pub(crate) fn new(iter: I, take: usize) -> TryTake<I> {
+        TryTake { iter, take }
+    }
+}
+
+impl<I> TryIterator for TryTake<I>
+where
+    I: TryIterator,
+{
+    type Item<'a> = I::Item<'a>
+    where
+        Self: 'a;
+
+    type Error = I::Error;
+
+    fn try_next(&mut self) -> Result<Option<Self::Item<'_>>, Self::Error> {
+        if self.take > 0 {
+            self.take -= 1;
+            self.iter.try_next()
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.iter.size_hint();
+        let upper = upper.map_or(self.take, |upper| upper.min(self.take));
+        (lower.min(self.take), Some(upper))
+    }
+}
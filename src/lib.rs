@@ -10,7 +10,6 @@
     explicit_outlives_requirements,
     missing_abi,
     noop_method_call,
-    pointer_structural_match,
     semicolon_in_expressions_from_macros,
     unused_import_braces,
     unused_lifetimes,
@@ -31,8 +30,31 @@
 
 #[cfg(feature = "alloc")]
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
-/// Rewrites `std` operators to use their GAT equivalents. Can be applied to any item or statement.
+/// Rewrites `std` operators to use their GAT equivalents. Can be applied to any item, including
+/// modules, impl blocks and const items, and to associated items of impls and traits. For smaller
+/// regions, such as a single expression or closure, see [`gatify_block!`].
+///
+/// Items inside the rewritten code with a `#[gatify]` of their own are left to that attribute, so
+/// they can use different arguments. Const contexts, such as const items and `const fn` bodies,
+/// aren't rewritten, as the GAT traits can't be called there, but closures inside them are.
+///
+/// ## Arguments
+///
+/// - `crate = path` sets the path to this crate, for use through a crate which re-exports it.
+///   Defaults to `::gat_std`.
+/// - `for_loops`, `index`, `deref` and `macros` enable only the listed rewrites, instead of all of
+///   them.
+/// - `skip(..)` disables the listed rewrites, such as `#[gatify(skip(deref))]`.
+///
+/// Expressions, statements, items and match arms inside the rewritten code can be excluded by
+/// marking them `#[gatify::skip]`. The attribute is removed by the macro.
+///
+/// Bad arguments and misplaced skip attributes are all reported at once, alongside the rewritten
+/// code. Values the rewritten operators don't support are reported at the expression, with a note
+/// of which traits were expected.
 ///
 /// ## Index
 ///
@@ -41,13 +63,188 @@ extern crate alloc;
 /// [`core::ops::Index`] - if the macro can't tell which impl is expected from context, it will
 /// error out, pointing to the operator that caused the error.
 ///
+/// `&a[i]` and `&mut a[i]` yield the output of the GAT traits directly. A bare `a[i]` in value
+/// position uses [`core::ops::Index`] if it is implemented, yielding the same place as the core
+/// operator, and otherwise yields an immutable place holding the output of [`ops::Index`]. This
+/// means `Copy` outputs and proxy values can be used directly, as in `a[0] + 1`. Like the core
+/// operator, it looks through up to two references or smart pointers, so `a: &BitVec` or
+/// `a: &&Vec<T>` can be indexed too.
+///
+/// An index used as a method receiver, as in `a[i].push(x)`, also tries the core operator first.
+/// The macro can't see whether the method takes `&mut self`, so it indexes mutably when `a` is a
+/// binding declared `mut` or holding a `&mut T`, possibly through fields, and the arguments don't
+/// use it. This picks [`core::ops::IndexMut`] or [`ops::IndexMut`], and otherwise
+/// [`core::ops::Index`] or [`ops::Index`]. A mutable receiver keeps `a` borrowed mutably for as
+/// long as the method's output is used, so a `&self` method returning a borrow, as in
+/// `let x = a[0].first()`, should go through an immutable binding or be marked
+/// `#[gatify::skip]`.
+///
+/// Assignment through an index, such as `a[i] = b`, dereferences the output of
+/// [`ops::IndexMut`], so it works for both plain references and proxy types like
+/// [`collections::BitMut`].
+///
+/// Nested indexing and field accesses between indexes, such as `&mut a.rows[i].cells[j]` or
+/// `a[i][j] = b`, call the GAT traits on each step, using [`ops::IndexMut`] throughout when the
+/// place is borrowed mutably or assigned to.
+///
+/// A `VecDeque` indexed immutably by a range, as in `&deque[1..3]`, yields a
+/// [`collections::DequeRange`]. This can't be an impl of [`ops::Index`], which would overlap with
+/// the blanket impl, so it's handled by the macro, and isn't available when calling the trait
+/// directly or indexing mutably. Use [`collections::DequeRangeMut::from`] for a mutable range.
+///
+/// ## Deref
+///
+/// The unary `*` operator is converted to use [`core::ops::Deref`] if it is implemented, and
+/// otherwise [`ops::Deref`] or [`ops::DerefMut`]. `&*a` and `&mut *a` yield the target directly,
+/// while a bare `*a` in value position yields an immutable place holding the target, so `Copy`
+/// targets and proxy values can be used but not moved out of, even from a `Box`.
+///
+/// `*` in place contexts is left to the core operator. These are `let` initializers, `match`,
+/// `if let` and `while let` scrutinees, assignments, method receivers such as `(*a).foo()` and
+/// field accesses such as `(*a).foo`. So is `&mut *a` where `a` is a local binding not declared
+/// `mut`, which can only be a reborrow of a `&mut T`. Use `&*a` or `&mut *a` to get the target of
+/// [`ops::Deref`] in these places.
+///
+/// `*` on a local binding declared with a reference or raw pointer type, or initialized by `&` or
+/// `&mut`, is also left to the core operator, so `&*a` reborrows for the whole lifetime of the
+/// reference instead of borrowing the binding. Other raw pointers are dereferenced as by the
+/// core operator, and still need an `unsafe` block.
+///
+/// ## Macros
+///
+/// Arguments of known `std` macros are rewritten, following each macro's grammar. These are the
+/// `format_args!` family, such as `println!`, `write!` and `panic!`, the `assert*!` macros,
+/// `dbg!`, `vec!`, and `matches!`, where only the scrutinee and guard are rewritten. They're
+/// recognized by a bare name or a path into `std`, `core` or `alloc`, such as `core::assert!`.
+/// Unknown macros, including user macros named by another path, are left untouched, as their
+/// arguments may not be expressions.
+///
+/// A bare name is left untouched while a `macro_rules!` of the same name defined inside the
+/// attributed code is in scope. The macro can't see definitions outside of it, so a user macro
+/// shadowing a `std` name from the surrounding module will still have its arguments rewritten -
+/// name it by a path, or mark the call `#[gatify::skip]`.
+///
 /// ## For Loops
 ///
 /// For loops are converted to use either [`core::iter::Iterator`] or [`iter::Iterator`], depending
 /// on which is implemented. If both are implemented, priority is given to the lending iterator.
+/// Loop labels and attributes are kept on the rewritten loop, so `break` and `continue` behave
+/// the same as in the original.
 ///
 pub use gat_std_proc::gatify;
 
+/// Rewrites `std` operators in a sequence of statements or items, in the same way as [`gatify`].
+/// The rewritten code isn't wrapped in a block, so `let` bindings and items stay visible after the
+/// macro, and it can be used at module level. In expression position, the macro can contain a
+/// single expression, or a block of statements in double braces, as in `gatify_block! {{ .. }}`.
+///
+/// Arguments can be given with an inner attribute at the start, such as
+/// `gatify_block! { #![gatify(skip(deref))] .. }`.
+///
+/// This is a separate macro because attribute and function-like macros can't share a name.
+pub use gat_std_proc::gatify_block;
+
+/// Implements [`iter::Iterator`] from an inherent impl block containing a
+/// `fn next(&mut self) -> Option<..>`, inferring the `Item` GAT from its return type. Lifetimes
+/// elided in the return type, or the named lifetime of `&mut self`, become the lifetime of the
+/// item.
+///
+/// `size_hint`, `advance_by` and `nth` in the block are moved into the trait impl too, and
+/// `next_back` into an impl of [`iter::DoubleEndedIterator`]. Any other items stay in an inherent
+/// impl. The path to this crate can be set with `#[lending_iterator(crate = path)]`.
+///
+/// Only impl blocks are supported. The attribute can't be put on `fn next` itself, as a method
+/// alone doesn't name the type to implement the trait for.
+///
+/// ```
+/// # use gat_std::iter::Iterator;
+/// struct Counter(u32);
+///
+/// #[gat_std::lending_iterator]
+/// impl Counter {
+///     fn next(&mut self) -> Option<&mut u32> {
+///         self.0 += 1;
+///         Some(&mut self.0)
+///     }
+/// }
+///
+/// assert_eq!(Counter(0).nth(2), Some(&mut 3));
+/// ```
+pub use gat_std_proc::lending_iterator;
+
+/// Creates an [`iter::Iterator`] from a block of code using `yield`, where the yielded values may
+/// borrow from the iterator's state until the next call to `next`. The block is compiled into a
+/// state machine on stable Rust, so `loop`, `while`, `if`, typed locals and early returns work
+/// across a `yield`. Other control flow can't contain one, as listed under the limitations below.
+///
+/// The state is listed after `move`, as `name: Type = value`, or `name: Type` to move a variable
+/// of the same name. The item type follows, with elided lifetimes borrowing from the iterator.
+/// Elided lifetimes in the types of the state become a lifetime of the iterator, so it can also
+/// borrow from its environment. The path to this crate can be set with
+/// `#![lending_iter(crate = path)]` at the start of the macro.
+///
+/// ```
+/// # use gat_std::iter::Iterator;
+/// let mut chunks = gat_std::lending_iter! {
+///     move(buf: Vec<u8> = vec![0; 6], size: usize = 2) -> &mut [u8] {
+///         let mut start: usize = 0;
+///         while start < buf.len() {
+///             yield &mut buf[start..start + size];
+///             start += size;
+///         }
+///     }
+/// };
+///
+/// let mut n = 0;
+/// while let Some(chunk) = chunks.next() {
+///     n += 1;
+///     chunk.fill(n);
+///     assert_eq!(chunk, [n, n]);
+/// }
+/// assert_eq!(n, 3);
+/// ```
+///
+/// ## Limitations
+///
+/// - A `yield` must be a statement, directly inside blocks, `if`, `else`, `loop` and `while`.
+///   `for` loops, `match`, `if let` and `while let` can't contain a `yield`.
+/// - A `let` before a `yield` in the same block is stored in the iterator, so it needs a type and
+///   a single name, and can't borrow from the rest of the state.
+/// - `return` can't have a value, and `?` can't be used, as they would return from `next`.
+/// - State can't be moved out of, as it is only borrowed by `next`.
+/// - The state is only visible to expressions and the arguments of known `std` macros, so it must
+///   be passed explicitly to format strings, as in `println!("{}", buf)`.
+/// - Generic parameters of the surrounding function can't be used in the types.
+pub use gat_std_proc::lending_iter;
+
+/// Derives [`iter::Iterator`] for a struct by forwarding to one of its fields. A struct with a
+/// single field forwards to it, otherwise the field must be marked `#[gat_std(forward)]`. The impl
+/// requires the field's type to implement the trait, so generic structs work as expected.
+///
+/// The path to this crate can be set with `#[gat_std(crate = path)]` on the struct, as for
+/// [`gatify`]. [`DoubleEndedIterator`](derive@DoubleEndedIterator), [`Index`](derive@Index) and
+/// [`IndexMut`](derive@IndexMut) are derived in the same way. There is no lending `ExactSize`
+/// trait to derive, but `size_hint` is forwarded, so an exact size is kept.
+pub use gat_std_proc::Iterator;
+
+/// Derives [`iter::DoubleEndedIterator`] by forwarding to a field, like
+/// [`Iterator`](derive@Iterator)
+pub use gat_std_proc::DoubleEndedIterator;
+
+/// Derives [`ops::Index`] by forwarding to a field, like [`Iterator`](derive@Iterator). The index
+/// types are set with `#[gat_std(index = Type)]` on the struct, which can be repeated, and default
+/// to `usize`. A generic index type would overlap with the blanket impl over
+/// [`core::ops::Index`].
+pub use gat_std_proc::Index;
+
+/// Derives [`ops::IndexMut`] by forwarding to a field, like [`Index`](derive@Index)
+pub use gat_std_proc::IndexMut;
+
+pub mod async_iter;
+pub mod borrow;
+pub mod collections;
+pub mod convert;
+pub mod io;
 pub mod iter;
 pub mod ops;
 
@@ -61,7 +258,7 @@ pub mod __impl {
         fn select(&self) -> Self::Selector;
     }
 
-    impl<T: crate::iter::IntoIterator> ViaLending for &IntoIter<T> {
+    impl<T: crate::iter::IntoIterator> ViaLending for &&IntoIter<T> {
         type Selector = Lending;
 
         fn select(&self) -> Self::Selector {
@@ -75,7 +272,360 @@ pub mod __impl {
         fn select(&self) -> Self::Selector;
     }
 
-    impl<T: core::iter::IntoIterator> ViaCore for IntoIter<T> {
+    impl<T: core::iter::IntoIterator> ViaCore for &IntoIter<T> {
+        type Selector = Core;
+
+        fn select(&self) -> Self::Selector {
+            Core
+        }
+    }
+
+    pub trait ViaRef {
+        type Selector;
+
+        fn select(&self) -> Self::Selector;
+    }
+
+    /// The fallback when no other selector applies, which reports what was expected
+    pub trait ViaNone {
+        type Selector;
+
+        fn select(&self) -> Self::Selector;
+    }
+
+    impl<T> ViaNone for IntoIter<T> {
+        type Selector = Unsupported;
+
+        fn select(&self) -> Self::Selector {
+            Unsupported
+        }
+    }
+
+    pub struct Deref<'a, T: ?Sized>(pub &'a T);
+
+    impl<T: ?Sized> ViaRef for &&Deref<'_, &T> {
+        type Selector = Ref;
+
+        fn select(&self) -> Self::Selector {
+            Ref
+        }
+    }
+
+    impl<T: ?Sized> ViaRef for &&Deref<'_, *const T> {
+        type Selector = Raw;
+
+        fn select(&self) -> Self::Selector {
+            Raw
+        }
+    }
+
+    impl<T: ?Sized> ViaRef for &&Deref<'_, *mut T> {
+        type Selector = Raw;
+
+        fn select(&self) -> Self::Selector {
+            Raw
+        }
+    }
+
+    impl<T: ?Sized + core::ops::Deref> ViaCore for &&&Deref<'_, T> {
+        type Selector = Core;
+
+        fn select(&self) -> Self::Selector {
+            Core
+        }
+    }
+
+    impl<T: ?Sized + crate::ops::Deref> ViaLending for &Deref<'_, T> {
+        type Selector = Lending;
+
+        fn select(&self) -> Self::Selector {
+            Lending
+        }
+    }
+
+    impl<T: ?Sized> ViaNone for Deref<'_, T> {
+        type Selector = Unsupported;
+
+        fn select(&self) -> Self::Selector {
+            Unsupported
+        }
+    }
+
+    pub struct Index<'a, T: ?Sized, I>(pub &'a T, pub I);
+
+    impl<T: ?Sized, I> ViaNone for Index<'_, T, I> {
+        type Selector = Unsupported;
+
+        fn select(&self) -> Self::Selector {
+            Unsupported
+        }
+    }
+
+    // Each level of autoderef tries the core operator before the GAT trait, like the core
+    // operator looks through references and smart pointers to find an impl
+
+    impl<T: ?Sized + core::ops::Index<I>, I> ViaCore for &&&&&&Index<'_, T, I> {
+        type Selector = Core;
+
+        fn select(&self) -> Self::Selector {
+            Core
+        }
+    }
+
+    impl<T: ?Sized + crate::ops::Index<I>, I> ViaLending for &&&&&Index<'_, T, I> {
+        type Selector = Lending;
+
+        fn select(&self) -> Self::Selector {
+            Lending
+        }
+    }
+
+    impl<T: ?Sized + core::ops::Deref, I> ViaRef for &&&&Index<'_, T, I>
+    where
+        T::Target: core::ops::Index<I>,
+    {
+        type Selector = Ref;
+
+        fn select(&self) -> Self::Selector {
+            Ref
+        }
+    }
+
+    impl<T: ?Sized + core::ops::Deref, I> ViaRef for &&&Index<'_, T, I>
+    where
+        T::Target: crate::ops::Index<I>,
+    {
+        type Selector = RefLending;
+
+        fn select(&self) -> Self::Selector {
+            RefLending
+        }
+    }
+
+    impl<T: ?Sized + core::ops::Deref, I> ViaRef for &&Index<'_, T, I>
+    where
+        T::Target: core::ops::Deref,
+        <T::Target as core::ops::Deref>::Target: core::ops::Index<I>,
+    {
+        type Selector = RefRef;
+
+        fn select(&self) -> Self::Selector {
+            RefRef
+        }
+    }
+
+    impl<T: ?Sized + core::ops::Deref, I> ViaRef for &Index<'_, T, I>
+    where
+        T::Target: core::ops::Deref,
+        <T::Target as core::ops::Deref>::Target: crate::ops::Index<I>,
+    {
+        type Selector = RefRefLending;
+
+        fn select(&self) -> Self::Selector {
+            RefRefLending
+        }
+    }
+
+    pub struct IndexMut<'a, T: ?Sized, I>(pub &'a mut T, pub I);
+
+    impl<T: ?Sized, I> ViaNone for IndexMut<'_, T, I> {
+        type Selector = Unsupported;
+
+        fn select(&self) -> Self::Selector {
+            Unsupported
+        }
+    }
+
+    impl<T: ?Sized + core::ops::IndexMut<I>, I> ViaCore for &&&&IndexMut<'_, T, I> {
+        type Selector = Core;
+
+        fn select(&self) -> Self::Selector {
+            Core
+        }
+    }
+
+    impl<T: ?Sized + crate::ops::IndexMut<I>, I> ViaLending for &&&IndexMut<'_, T, I> {
+        type Selector = Lending;
+
+        fn select(&self) -> Self::Selector {
+            Lending
+        }
+    }
+
+    impl<T: ?Sized + core::ops::DerefMut, I> ViaRef for &&IndexMut<'_, T, I>
+    where
+        T::Target: core::ops::IndexMut<I>,
+    {
+        type Selector = Ref;
+
+        fn select(&self) -> Self::Selector {
+            Ref
+        }
+    }
+
+    impl<T: ?Sized + core::ops::DerefMut, I> ViaRef for &IndexMut<'_, T, I>
+    where
+        T::Target: crate::ops::IndexMut<I>,
+    {
+        type Selector = RefLending;
+
+        fn select(&self) -> Self::Selector {
+            RefLending
+        }
+    }
+
+    /// Selects a `VecDeque` for range indexing. `ops::Index` can't be implemented for it, as it
+    /// would overlap with the blanket impl over `core::ops::Index`.
+    pub trait ViaDeque {
+        type Selector;
+
+        fn select(&self) -> Self::Selector;
+    }
+
+    #[cfg(feature = "alloc")]
+    impl<T, I> ViaDeque for &&&&&&&Index<'_, T, I>
+    where
+        T: ?Sized + AsDeque,
+        I: core::ops::RangeBounds<usize>,
+    {
+        type Selector = Deque;
+
+        fn select(&self) -> Self::Selector {
+            Deque
+        }
+    }
+
+    /// The value at the start of a borrowed place containing an index
+    pub struct Base<'a, T: ?Sized>(pub &'a T);
+
+    #[cfg(feature = "alloc")]
+    impl<T: ?Sized + AsDeque> ViaDeque for Base<'_, T> {
+        type Selector = Deque;
+
+        fn select(&self) -> Self::Selector {
+            Deque
+        }
+    }
+
+    impl<T: ?Sized> ViaCore for &Base<'_, T> {
+        type Selector = Core;
+
+        fn select(&self) -> Self::Selector {
+            Core
+        }
+    }
+
+    /// A `VecDeque`, or a reference to one
+    #[cfg(feature = "alloc")]
+    pub trait AsDeque {
+        type Item;
+
+        fn as_deque(&self) -> &alloc::collections::VecDeque<Self::Item>;
+    }
+
+    #[cfg(feature = "alloc")]
+    impl<T> AsDeque for alloc::collections::VecDeque<T> {
+        type Item = T;
+
+        fn as_deque(&self) -> &alloc::collections::VecDeque<T> {
+            self
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    impl<D: ?Sized + AsDeque> AsDeque for &D {
+        type Item = D::Item;
+
+        fn as_deque(&self) -> &alloc::collections::VecDeque<Self::Item> {
+            (**self).as_deque()
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    impl<D: ?Sized + AsDeque> AsDeque for &mut D {
+        type Item = D::Item;
+
+        fn as_deque(&self) -> &alloc::collections::VecDeque<Self::Item> {
+            (**self).as_deque()
+        }
+    }
+
+    /// An index into a `VecDeque`, where ranges yield a [`DequeRange`](crate::collections::DequeRange)
+    #[cfg(feature = "alloc")]
+    pub trait DequeIndex<'a, T: 'a> {
+        type Output;
+
+        fn index(self, deque: &'a alloc::collections::VecDeque<T>) -> Self::Output;
+    }
+
+    #[cfg(feature = "alloc")]
+    impl<'a, T: 'a> DequeIndex<'a, T> for usize {
+        type Output = &'a T;
+
+        fn index(self, deque: &'a alloc::collections::VecDeque<T>) -> Self::Output {
+            &deque[self]
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    macro_rules! impl_deque_index {
+        ($($range:ty),* $(,)?) => {
+            $(
+            impl<'a, T: 'a> DequeIndex<'a, T> for $range {
+                type Output = crate::collections::DequeRange<'a, T>;
+
+                fn index(self, deque: &'a alloc::collections::VecDeque<T>) -> Self::Output {
+                    crate::collections::DequeRange::from(deque).range(self)
+                }
+            }
+            )*
+        };
+    }
+
+    #[cfg(feature = "alloc")]
+    impl_deque_index!(
+        core::ops::Range<usize>,
+        core::ops::RangeFrom<usize>,
+        core::ops::RangeTo<usize>,
+        core::ops::RangeInclusive<usize>,
+        core::ops::RangeToInclusive<usize>,
+        core::ops::RangeFull,
+    );
+
+    /// A `VecDeque` at the start of a borrowed place, which can be indexed by ranges
+    #[cfg(feature = "alloc")]
+    pub struct DequeBase<'a, T>(&'a alloc::collections::VecDeque<T>);
+
+    #[cfg(feature = "alloc")]
+    impl<'a, T> DequeBase<'a, T> {
+        pub fn index<I: DequeIndex<'a, T>>(self, idx: I) -> I::Output {
+            idx.index(self.0)
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    pub struct Deque;
+
+    #[cfg(feature = "alloc")]
+    impl Deque {
+        pub fn index_base<'a, T: ?Sized + AsDeque>(
+            self,
+            Base(val): Base<'a, T>,
+        ) -> DequeBase<'a, T::Item> {
+            DequeBase(val.as_deque())
+        }
+
+        pub fn index_place<'a, T: ?Sized + AsDeque, I: core::ops::RangeBounds<usize>>(
+            self,
+            Index(val, idx): Index<'a, T, I>,
+        ) -> Place<crate::collections::DequeRange<'a, T::Item>> {
+            Place(crate::collections::DequeRange::from(val.as_deque()).range(idx))
+        }
+    }
+
+    pub struct DerefMut<'a, T: ?Sized>(pub &'a mut T);
+
+    impl<T: ?Sized + core::ops::DerefMut> ViaCore for &&&DerefMut<'_, T> {
         type Selector = Core;
 
         fn select(&self) -> Self::Selector {
@@ -83,19 +633,386 @@ pub mod __impl {
         }
     }
 
+    impl<T: ?Sized + crate::ops::DerefMut> ViaLending for &DerefMut<'_, T> {
+        type Selector = Lending;
+
+        fn select(&self) -> Self::Selector {
+            Lending
+        }
+    }
+
+    impl<T: ?Sized> ViaRef for &&DerefMut<'_, *mut T> {
+        type Selector = Raw;
+
+        fn select(&self) -> Self::Selector {
+            Raw
+        }
+    }
+
+    impl<T: ?Sized> ViaNone for DerefMut<'_, T> {
+        type Selector = Unsupported;
+
+        fn select(&self) -> Self::Selector {
+            Unsupported
+        }
+    }
+
+    /// Holds a GAT output so it can be dereferenced like the place a core operator yields
+    pub struct Place<T>(pub T);
+
+    impl<T> core::ops::Deref for Place<T> {
+        type Target = T;
+
+        fn deref(&self) -> &Self::Target {
+            &self.0
+        }
+    }
+
+    /// Holds a GAT output so it can be dereferenced like the place a core operator yields, and
+    /// used as a `&mut self` method receiver
+    pub struct PlaceMut<T>(pub T);
+
+    impl<T> core::ops::Deref for PlaceMut<T> {
+        type Target = T;
+
+        fn deref(&self) -> &Self::Target {
+            &self.0
+        }
+    }
+
+    impl<T> core::ops::DerefMut for PlaceMut<T> {
+        fn deref_mut(&mut self) -> &mut Self::Target {
+            &mut self.0
+        }
+    }
+
+    /// A raw pointer, which can only be dereferenced in an `unsafe` context
+    pub trait RawPointer: Copy {
+        type Target: ?Sized;
+
+        fn as_const(self) -> *const Self::Target;
+    }
+
+    impl<T: ?Sized> RawPointer for *const T {
+        type Target = T;
+
+        fn as_const(self) -> *const T {
+            self
+        }
+    }
+
+    impl<T: ?Sized> RawPointer for *mut T {
+        type Target = T;
+
+        fn as_const(self) -> *const T {
+            self
+        }
+    }
+
+    /// Dereferences a raw pointer. A place is left to the core operator, and borrowing the target
+    /// calls an `unsafe` method, so either still needs an `unsafe` block.
+    pub struct Raw;
+
+    impl Raw {
+        pub fn deref_place<P: RawPointer>(self, Deref(&ptr): Deref<'_, P>) -> P {
+            ptr
+        }
+
+        /// # Safety
+        ///
+        /// The pointer must be valid to dereference, as for the core operator
+        pub unsafe fn deref<'a, P: RawPointer>(self, Deref(&ptr): Deref<'_, P>) -> &'a P::Target {
+            // SAFETY: Our caller upholds the requirements of the dereference
+            unsafe { &*ptr.as_const() }
+        }
+
+        /// # Safety
+        ///
+        /// The pointer must be valid to dereference mutably, as for the core operator
+        pub unsafe fn deref_mut<'a, T: ?Sized>(
+            self,
+            DerefMut(ptr): DerefMut<'_, *mut T>,
+        ) -> &'a mut T {
+            // SAFETY: Our caller upholds the requirements of the dereference
+            unsafe { &mut **ptr }
+        }
+    }
+
+    /// Dereferences a plain reference, keeping its original lifetime instead of the lifetime
+    /// of the borrow of it
+    pub struct Ref;
+
+    impl Ref {
+        pub fn deref<'a, T: ?Sized>(self, Deref(&val): Deref<'_, &'a T>) -> &'a T {
+            val
+        }
+
+        pub fn deref_place<'a, T: ?Sized>(self, Deref(&val): Deref<'_, &'a T>) -> &'a T {
+            val
+        }
+
+        pub fn index_place<'a, T: ?Sized + core::ops::Deref, I>(
+            self,
+            Index(val, idx): Index<'a, T, I>,
+        ) -> &'a <T::Target as core::ops::Index<I>>::Output
+        where
+            T::Target: core::ops::Index<I>,
+        {
+            core::ops::Index::index(&**val, idx)
+        }
+
+        pub fn index_place_mut<'a, T: ?Sized + core::ops::DerefMut, I>(
+            self,
+            IndexMut(val, idx): IndexMut<'a, T, I>,
+        ) -> &'a mut <T::Target as core::ops::Index<I>>::Output
+        where
+            T::Target: core::ops::IndexMut<I>,
+        {
+            core::ops::IndexMut::index_mut(&mut **val, idx)
+        }
+    }
+
+    /// Indexes the target of a reference or smart pointer with [`crate::ops::Index`] or
+    /// [`crate::ops::IndexMut`]
+    pub struct RefLending;
+
+    impl RefLending {
+        pub fn index_place_mut<'a, T: ?Sized + core::ops::DerefMut, I>(
+            self,
+            IndexMut(val, idx): IndexMut<'a, T, I>,
+        ) -> PlaceMut<<T::Target as crate::ops::IndexMut<I>>::OutputMut<'a>>
+        where
+            T::Target: crate::ops::IndexMut<I>,
+        {
+            PlaceMut(crate::ops::IndexMut::index_mut(&mut **val, idx))
+        }
+
+        pub fn index_place<'a, T: ?Sized + core::ops::Deref, I>(
+            self,
+            Index(val, idx): Index<'a, T, I>,
+        ) -> Place<<T::Target as crate::ops::Index<I>>::Output<'a>>
+        where
+            T::Target: crate::ops::Index<I>,
+        {
+            Place(crate::ops::Index::index(&**val, idx))
+        }
+    }
+
+    /// Indexes through two references or smart pointers with [`core::ops::Index`]
+    pub struct RefRef;
+
+    impl RefRef {
+        pub fn index_place<'a, T: ?Sized + core::ops::Deref, I>(
+            self,
+            Index(val, idx): Index<'a, T, I>,
+        ) -> &'a <<T::Target as core::ops::Deref>::Target as core::ops::Index<I>>::Output
+        where
+            T::Target: core::ops::Deref,
+            <T::Target as core::ops::Deref>::Target: core::ops::Index<I>,
+        {
+            core::ops::Index::index(&***val, idx)
+        }
+    }
+
+    /// Indexes through two references or smart pointers with [`crate::ops::Index`]
+    pub struct RefRefLending;
+
+    impl RefRefLending {
+        #[allow(clippy::type_complexity)]
+        pub fn index_place<'a, T: ?Sized + core::ops::Deref, I>(
+            self,
+            Index(val, idx): Index<'a, T, I>,
+        ) -> Place<<<T::Target as core::ops::Deref>::Target as crate::ops::Index<I>>::Output<'a>>
+        where
+            T::Target: core::ops::Deref,
+            <T::Target as core::ops::Deref>::Target: crate::ops::Index<I>,
+        {
+            Place(crate::ops::Index::index(&***val, idx))
+        }
+    }
+
     pub struct Lending;
 
     impl Lending {
         pub fn into_iter<T: crate::iter::IntoIterator>(self, iter: IntoIter<T>) -> T::IntoIter {
             iter.0.into_iter()
         }
+
+        pub fn deref<'a, T: ?Sized + crate::ops::Deref>(self, val: Deref<'a, T>) -> T::Target<'a> {
+            val.0.deref()
+        }
+
+        pub fn deref_mut<'a, T: ?Sized + crate::ops::DerefMut>(
+            self,
+            val: DerefMut<'a, T>,
+        ) -> T::TargetMut<'a> {
+            val.0.deref_mut()
+        }
+
+        pub fn deref_place<'a, T: ?Sized + crate::ops::Deref>(
+            self,
+            val: Deref<'a, T>,
+        ) -> Place<T::Target<'a>> {
+            Place(val.0.deref())
+        }
+
+        pub fn index_place<'a, T: ?Sized + crate::ops::Index<I>, I>(
+            self,
+            Index(val, idx): Index<'a, T, I>,
+        ) -> Place<T::Output<'a>> {
+            Place(crate::ops::Index::index(val, idx))
+        }
+
+        pub fn index_place_mut<'a, T: ?Sized + crate::ops::IndexMut<I>, I>(
+            self,
+            IndexMut(val, idx): IndexMut<'a, T, I>,
+        ) -> PlaceMut<T::OutputMut<'a>> {
+            PlaceMut(crate::ops::IndexMut::index_mut(val, idx))
+        }
     }
 
     pub struct Core;
 
     impl Core {
+        pub fn index_base<'a, T: ?Sized>(self, Base(val): Base<'a, T>) -> &'a T {
+            val
+        }
+
         pub fn into_iter<T: core::iter::IntoIterator>(self, iter: IntoIter<T>) -> T::IntoIter {
             iter.0.into_iter()
         }
+
+        pub fn deref<'a, T: ?Sized + core::ops::Deref>(self, val: Deref<'a, T>) -> &'a T::Target {
+            val.0
+        }
+
+        pub fn deref_mut<'a, T: ?Sized + core::ops::DerefMut>(
+            self,
+            val: DerefMut<'a, T>,
+        ) -> &'a mut T::Target {
+            val.0
+        }
+
+        pub fn deref_place<'a, T: ?Sized + core::ops::Deref>(
+            self,
+            val: Deref<'a, T>,
+        ) -> &'a T::Target {
+            val.0
+        }
+
+        pub fn index_place<'a, T: ?Sized + core::ops::Index<I>, I>(
+            self,
+            Index(val, idx): Index<'a, T, I>,
+        ) -> &'a T::Output {
+            core::ops::Index::index(val, idx)
+        }
+
+        pub fn index_place_mut<'a, T: ?Sized + core::ops::IndexMut<I>, I>(
+            self,
+            IndexMut(val, idx): IndexMut<'a, T, I>,
+        ) -> &'a mut T::Output {
+            core::ops::IndexMut::index_mut(val, idx)
+        }
+    }
+
+    #[diagnostic::on_unimplemented(
+        message = "`{Self}` can't be iterated over",
+        label = "not an iterator",
+        note = "`{Self}` implements neither `gat_std::iter::IntoIterator` nor `core::iter::IntoIterator`"
+    )]
+    pub trait Iterable {
+        type IntoIter: crate::iter::Iterator;
+
+        fn unsupported(self) -> Self::IntoIter;
+    }
+
+    #[diagnostic::on_unimplemented(
+        message = "`{Self}` can't be dereferenced",
+        label = "can't be dereferenced",
+        note = "`{Self}` implements neither `gat_std::ops::Deref` nor `core::ops::Deref`"
+    )]
+    pub trait Derefable {
+        type Target: ?Sized;
+
+        fn unsupported(&self) -> &Self::Target;
+    }
+
+    #[diagnostic::on_unimplemented(
+        message = "`{Self}` can't be mutably dereferenced",
+        label = "can't be mutably dereferenced",
+        note = "`{Self}` implements neither `gat_std::ops::DerefMut` nor `core::ops::DerefMut`"
+    )]
+    pub trait DerefableMut: Derefable {
+        fn unsupported_mut(&mut self) -> &mut Self::Target;
+    }
+
+    #[diagnostic::on_unimplemented(
+        message = "`{Self}` can't be indexed by `{I}`",
+        label = "can't be indexed by `{I}`",
+        note = "`{Self}` implements neither `gat_std::ops::Index<{I}>` nor `core::ops::Index<{I}>`"
+    )]
+    pub trait Indexable<I> {
+        type Output: ?Sized;
+
+        fn unsupported(&self, idx: I) -> &Self::Output;
+    }
+
+    #[diagnostic::on_unimplemented(
+        message = "`{Self}` can't be mutably indexed by `{I}`",
+        label = "can't be mutably indexed by `{I}`",
+        note = "`{Self}` implements neither `gat_std::ops::IndexMut<{I}>` nor `core::ops::IndexMut<{I}>`"
+    )]
+    pub trait IndexableMut<I>: Indexable<I> {
+        fn unsupported_mut(&mut self, idx: I) -> &mut Self::Output;
+    }
+
+    /// Gets a local of `lending_iter!`. Its slot is set by its `let` and cleared when its block
+    /// is left, so it's always set while its name is in scope. Checking that costs a branch, but
+    /// skipping it would need `unsafe` in the caller's crate.
+    #[track_caller]
+    pub fn local<T>(slot: &mut Option<T>) -> &mut T {
+        match slot {
+            Some(val) => val,
+            None => unreachable!("`lending_iter!` local used before it was set"),
+        }
+    }
+
+    /// Selected when no rewrite applies. Its bounds are never met, so the error names the
+    /// traits which were expected.
+    pub struct Unsupported;
+
+    impl Unsupported {
+        pub fn into_iter<T: Iterable>(self, iter: IntoIter<T>) -> T::IntoIter {
+            iter.0.unsupported()
+        }
+
+        pub fn deref<'a, T: ?Sized + Derefable>(self, val: Deref<'a, T>) -> &'a T::Target {
+            val.0.unsupported()
+        }
+
+        pub fn deref_mut<'a, T: ?Sized + DerefableMut>(
+            self,
+            val: DerefMut<'a, T>,
+        ) -> &'a mut T::Target {
+            val.0.unsupported_mut()
+        }
+
+        pub fn deref_place<'a, T: ?Sized + Derefable>(self, val: Deref<'a, T>) -> &'a T::Target {
+            val.0.unsupported()
+        }
+
+        pub fn index_place<'a, T: ?Sized + Indexable<I>, I>(
+            self,
+            Index(val, idx): Index<'a, T, I>,
+        ) -> &'a T::Output {
+            val.unsupported(idx)
+        }
+
+        pub fn index_place_mut<'a, T: ?Sized + IndexableMut<I>, I>(
+            self,
+            IndexMut(val, idx): IndexMut<'a, T, I>,
+        ) -> &'a mut T::Output {
+            val.unsupported_mut(idx)
+        }
     }
 }
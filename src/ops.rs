@@ -1,5 +1,7 @@
 //! GAT equivalents of `std` operators
 
+use crate::iter::Iterator;
+
 /// Index operator for immutable contexts. As opposed to `std`, the returned value can be a
 /// non-reference. This allows custom reference types for things like multi-dimensional matrices.
 pub trait Index<T> {
@@ -51,3 +53,284 @@ where
         <Self as core::ops::IndexMut<I>>::index_mut(self, idx)
     }
 }
+
+/// Dereference operator for immutable contexts. As opposed to `std`, the target can be a
+/// non-reference. This allows types such as projection guards to deref into custom view types.
+pub trait Deref {
+    /// The output type of dereferencing this value
+    type Target<'a>
+    where
+        Self: 'a;
+
+    /// Dereference this value immutably
+    fn deref(&self) -> Self::Target<'_>;
+}
+
+/// Dereference operator for mutable contexts. As opposed to `std`, the target can be a
+/// non-reference. This allows types such as projection guards to deref into custom view types.
+pub trait DerefMut: Deref {
+    /// The output type of dereferencing this value
+    type TargetMut<'a>
+    where
+        Self: 'a;
+
+    /// Dereference this value mutably
+    fn deref_mut(&mut self) -> Self::TargetMut<'_>;
+}
+
+impl<T: ?Sized> Deref for T
+where
+    T: core::ops::Deref,
+    T::Target: 'static,
+{
+    type Target<'a> = &'a <T as core::ops::Deref>::Target
+    where
+        Self: 'a;
+
+    fn deref(&self) -> Self::Target<'_> {
+        <Self as core::ops::Deref>::deref(self)
+    }
+}
+
+impl<T: ?Sized> DerefMut for T
+where
+    T: core::ops::DerefMut,
+    T::Target: 'static,
+{
+    type TargetMut<'a> = &'a mut <T as core::ops::Deref>::Target
+    where
+        Self: 'a;
+
+    fn deref_mut(&mut self) -> Self::TargetMut<'_> {
+        <Self as core::ops::DerefMut>::deref_mut(self)
+    }
+}
+
+/// A function called mutably on the items lent by `I`, whose output may borrow from the item.
+/// Closures can't name an output type that depends on the lifetime of their argument, which
+/// this trait allows via [`FnMutLending::Output`].
+///
+/// Closures of the shape `for<'a> FnMut(&'a mut A) -> &'a O` implement this for iterators lending
+/// `&'a mut A`, and [`SharedLending`] and [`OptionLending`] wrap closures taking `&'a A` or
+/// returning `Option<&'a O>`. The [`lending!`](crate::lending) macro can help the compiler infer
+/// these closure shapes.
+///
+/// These closure impls have to name the item for every lifetime `'a`. As [`Iterator::Item`]
+/// requires `Self: 'a`, the compiler can currently only prove that for `'static` iterators, so
+/// closures can't be used with iterators that borrow, such as one lending from a `&'s mut [T]`:
+///
+/// ```compile_fail
+/// # use gat_std::iter::Iterator;
+/// # struct PairsMut<'s>(&'s mut [(u8, u8)], usize);
+/// # impl<'s> Iterator for PairsMut<'s> {
+/// #     type Item<'a> = &'a mut (u8, u8) where Self: 'a;
+/// #     fn next(&mut self) -> Option<Self::Item<'_>> {
+/// #         let pair = self.0.get_mut(self.1)?;
+/// #         self.1 += 1;
+/// #         Some(pair)
+/// #     }
+/// # }
+/// fn first_second(pairs: &mut [(u8, u8)]) -> Option<u8> {
+///     PairsMut(pairs, 0)
+///         .map_lending(gat_std::lending!(|pair: &mut (u8, u8)| &pair.1))
+///         .next()
+///         .copied()
+/// }
+/// ```
+///
+/// Other functions can implement this trait directly, for any iterator and any output, such as a
+/// view borrowing from the item:
+///
+/// ```
+/// # use gat_std::iter::Iterator;
+/// # struct PairsMut<'s>(&'s mut [(u8, u8)], usize);
+/// # impl<'s> Iterator for PairsMut<'s> {
+/// #     type Item<'a> = &'a mut (u8, u8) where Self: 'a;
+/// #     fn next(&mut self) -> Option<Self::Item<'_>> {
+/// #         let pair = self.0.get_mut(self.1)?;
+/// #         self.1 += 1;
+/// #         Some(pair)
+/// #     }
+/// # }
+/// # use gat_std::ops::FnMutLending;
+/// struct Second;
+///
+/// impl<'s> FnMutLending<PairsMut<'s>> for Second {
+///     type Output<'a> = &'a u8
+///     where
+///         PairsMut<'s>: 'a;
+///
+///     fn call_mut<'a>(&mut self, pair: &'a mut (u8, u8)) -> &'a u8
+///     where
+///         PairsMut<'s>: 'a,
+///     {
+///         &pair.1
+///     }
+/// }
+///
+/// let mut pairs = [(1, 2), (3, 4)];
+/// let mut seconds = PairsMut(&mut pairs, 0).map_lending(Second);
+/// assert_eq!(seconds.next(), Some(&2));
+/// assert_eq!(seconds.next(), Some(&4));
+/// ```
+pub trait FnMutLending<I: ?Sized + Iterator> {
+    /// The output type of calling this function
+    type Output<'a>
+    where
+        I: 'a;
+
+    /// Call this function with an item
+    fn call_mut<'a>(&mut self, item: I::Item<'a>) -> Self::Output<'a>
+    where
+        I: 'a;
+}
+
+/// A function called immutably on the items lent by `I`, whose output may borrow from the item.
+/// See [`FnMutLending`] for details.
+pub trait FnLending<I: ?Sized + Iterator>: FnMutLending<I> {
+    /// Call this function with an item
+    fn call<'a>(&self, item: I::Item<'a>) -> Self::Output<'a>
+    where
+        I: 'a;
+}
+
+impl<I: ?Sized, F, A: ?Sized, O: ?Sized> FnMutLending<I> for F
+where
+    I: for<'a> Iterator<Item<'a> = &'a mut A> + 'static,
+    F: for<'a> FnMut(&'a mut A) -> &'a O,
+    O: 'static,
+{
+    type Output<'a> = &'a O
+    where
+        I: 'a;
+
+    fn call_mut<'a>(&mut self, item: I::Item<'a>) -> Self::Output<'a> {
+        self(item)
+    }
+}
+
+impl<I: ?Sized, F, A: ?Sized, O: ?Sized> FnLending<I> for F
+where
+    I: for<'a> Iterator<Item<'a> = &'a mut A> + 'static,
+    F: for<'a> Fn(&'a mut A) -> &'a O,
+    O: 'static,
+{
+    fn call<'a>(&self, item: I::Item<'a>) -> Self::Output<'a> {
+        self(item)
+    }
+}
+
+/// A closure taking items lent as `&'a A`, whose output borrows from them. Created by the
+/// [`lending!`](crate::lending) macro.
+pub struct SharedLending<F>(pub F);
+
+impl<I: ?Sized, F, A: ?Sized, O: ?Sized> FnMutLending<I> for SharedLending<F>
+where
+    I: for<'a> Iterator<Item<'a> = &'a A> + 'static,
+    F: for<'a> FnMut(&'a A) -> &'a O,
+    O: 'static,
+{
+    type Output<'a> = &'a O
+    where
+        I: 'a;
+
+    fn call_mut<'a>(&mut self, item: I::Item<'a>) -> Self::Output<'a> {
+        (self.0)(item)
+    }
+}
+
+impl<I: ?Sized, F, A: ?Sized, O: ?Sized> FnLending<I> for SharedLending<F>
+where
+    I: for<'a> Iterator<Item<'a> = &'a A> + 'static,
+    F: for<'a> Fn(&'a A) -> &'a O,
+    O: 'static,
+{
+    fn call<'a>(&self, item: I::Item<'a>) -> Self::Output<'a> {
+        (self.0)(item)
+    }
+}
+
+/// A closure taking items lent as `&'a mut A`, whose output may be a reference borrowing from
+/// them. Created by the [`lending!`](crate::lending) macro, and useful with
+/// [`Iterator::filter_map_lending`].
+pub struct OptionLending<F>(pub F);
+
+impl<I: ?Sized, F, A: ?Sized, O: ?Sized> FnMutLending<I> for OptionLending<F>
+where
+    I: for<'a> Iterator<Item<'a> = &'a mut A> + 'static,
+    F: for<'a> FnMut(&'a mut A) -> Option<&'a O>,
+    O: 'static,
+{
+    type Output<'a> = Option<&'a O>
+    where
+        I: 'a;
+
+    fn call_mut<'a>(&mut self, item: I::Item<'a>) -> Self::Output<'a> {
+        (self.0)(item)
+    }
+}
+
+impl<I: ?Sized, F, A: ?Sized, O: ?Sized> FnLending<I> for OptionLending<F>
+where
+    I: for<'a> Iterator<Item<'a> = &'a mut A> + 'static,
+    F: for<'a> Fn(&'a mut A) -> Option<&'a O>,
+    O: 'static,
+{
+    fn call<'a>(&self, item: I::Item<'a>) -> Self::Output<'a> {
+        (self.0)(item)
+    }
+}
+
+#[doc(hidden)]
+pub fn __lending_shared<A: ?Sized, O: ?Sized, F>(f: F) -> SharedLending<F>
+where
+    F: for<'a> FnMut(&'a A) -> &'a O,
+{
+    SharedLending(f)
+}
+
+#[doc(hidden)]
+pub fn __lending_option<A: ?Sized, O: ?Sized, F>(f: F) -> OptionLending<F>
+where
+    F: for<'a> FnMut(&'a mut A) -> Option<&'a O>,
+{
+    OptionLending(f)
+}
+
+#[doc(hidden)]
+pub fn __lending<A: ?Sized, O: ?Sized, F>(f: F) -> F
+where
+    F: for<'a> FnMut(&'a mut A) -> &'a O,
+{
+    f
+}
+
+/// Coerce a closure taking a reference into one whose output borrows from its argument, so it can
+/// be used as a [`FnMutLending`] or [`FnLending`]. The argument type must be annotated.
+///
+/// A closure taking `&mut A` is returned as-is, while one taking `&A` is wrapped in a
+/// [`SharedLending`]. Writing `-> Option` before a block body wraps a closure taking `&mut A` and
+/// returning `Option<&O>` in an [`OptionLending`].
+///
+/// ```
+/// # use gat_std::lending;
+/// let mut first = lending!(|pair: &mut (u8, u8)| &pair.0);
+/// assert_eq!(*first(&mut (1, 2)), 1);
+///
+/// let mut odd = lending!(|pair: &mut (u8, u8)| -> Option {
+///     (pair.0 % 2 == 1).then_some(&pair.1)
+/// });
+/// assert_eq!((odd.0)(&mut (1, 2)), Some(&2));
+/// ```
+#[macro_export]
+macro_rules! lending {
+    (|$arg:ident: &mut $ty:ty| -> Option $body:block) => {
+        $crate::ops::__lending_option::<$ty, _, _>(|$arg: &mut $ty| $body)
+    };
+    (|$arg:ident: &mut $ty:ty| $body:expr) => {
+        $crate::ops::__lending::<$ty, _, _>(|$arg: &mut $ty| $body)
+    };
+    (|$arg:ident: &$ty:ty| $body:expr) => {
+        $crate::ops::__lending_shared::<$ty, _, _>(|$arg: &$ty| $body)
+    };
+}
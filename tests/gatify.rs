@@ -0,0 +1,454 @@
+use gat_std::collections::{BitVec, RingBuffer};
+use gat_std::gatify;
+use gat_std::ops::{Deref, DerefMut};
+
+/// A pair of values which derefs to a proxy of the first
+struct Pair(u32, u32);
+
+impl Deref for Pair {
+    type Target<'a> = &'a u32;
+
+    fn deref(&self) -> Self::Target<'_> {
+        &self.0
+    }
+}
+
+impl DerefMut for Pair {
+    type TargetMut<'a> = &'a mut u32;
+
+    fn deref_mut(&mut self) -> Self::TargetMut<'_> {
+        &mut self.0
+    }
+}
+
+#[gatify]
+fn raw(ptr: *const i32) -> i32 {
+    // SAFETY: Callers pass a valid pointer
+    unsafe { *ptr }
+}
+
+#[gatify]
+fn reborrow(val: &mut i32) -> &i32 {
+    &*val
+}
+
+#[gatify]
+fn reborrow_mut(val: &mut Vec<i32>) -> &mut Vec<i32> {
+    &mut *val
+}
+
+#[test]
+#[gatify]
+fn deref_pointers() {
+    let mut val = 3;
+    assert_eq!(raw(&val), 3);
+    assert_eq!(*reborrow(&mut val), 3);
+    let mut vec = vec![1];
+    reborrow_mut(&mut vec).push(2);
+    assert_eq!(vec, [1, 2]);
+
+    // Raw pointers of an unknown type go through a selector, which still needs `unsafe`
+    let mut ptr = vec.as_mut_ptr();
+    // SAFETY: The vector is still alive, and has at least one element
+    unsafe {
+        *&mut *ptr += 10;
+        assert_eq!(*ptr, 11);
+        assert_eq!(*&*ptr, 11);
+    }
+    assert_eq!(vec, [11, 2]);
+}
+
+#[test]
+#[gatify]
+fn deref_lending() {
+    let mut pair = Pair(1, 2);
+    *&mut *pair += 1;
+    assert_eq!(*&*pair, 2);
+    assert_eq!(*pair, &2);
+    assert_eq!(pair.1, 2);
+
+    let boxed = Box::new(5);
+    assert_eq!(*boxed + 1, 6);
+}
+
+/// Holds its rows behind a field, to index them through `&mut self`
+struct Rows(Vec<Vec<u8>>);
+
+impl Rows {
+    #[gatify]
+    fn push(&mut self, row: usize, val: u8) {
+        self.0[row].push(val);
+    }
+}
+
+#[test]
+#[gatify]
+fn index_receivers() {
+    let mut bits = BitVec::from_elem(false, 3);
+    bits[1].set(true);
+    assert_eq!(bits[1].then_some(1), Some(1));
+    let shared = &bits;
+    assert_eq!(shared[2].then_some(2), None);
+
+    let mut ring = RingBuffer::<u8, 4>::new();
+    for val in [1, 2, 3] {
+        ring.push_back(val).unwrap();
+    }
+    assert_eq!(ring[1..].len(), 2);
+    *ring[1..].get_mut(0).unwrap() += 10;
+    assert_eq!(ring[1], 12);
+
+    let mut rows = Rows(vec![vec![1]]);
+    rows.push(0, 2);
+    let nested = &mut rows.0;
+    let len = nested[0].len();
+    nested[0].push(len as u8 + 1);
+    assert!(nested[0][0].cmp(&nested[0][2]).is_lt());
+    assert_eq!(rows.0[0], [1, 2, 3]);
+}
+
+#[test]
+#[gatify]
+fn index_bare() {
+    let mut nums = vec![0, 1, 2];
+    nums[1] = 2;
+    let first = &mut nums[0];
+    *first = 1;
+    assert_eq!(nums, [1, 2, 2]);
+
+    // Core outputs are dereferenced, and GAT outputs passed through
+    let words = vec![String::from("a"), String::from("bc")];
+    let bits = BitVec::from_elem(true, 3);
+    assert_eq!(nums[0] + nums[1], 3);
+    assert_eq!(words[1].len(), 2);
+    assert!(bits[2]);
+    let slice = &words[..];
+    assert_eq!(slice[0], "a");
+
+    // Through references
+    let (bits_ref, words_ref) = (&bits, &&words);
+    assert!(bits_ref[0]);
+    assert_eq!(words_ref[0], "a");
+}
+
+struct Row {
+    cells: Vec<u32>,
+}
+
+struct Grid {
+    rows: Vec<Row>,
+}
+
+#[test]
+#[gatify]
+fn index_nested() {
+    let mut matrix = vec![vec![1, 2], vec![3, 4]];
+    matrix[1][0] += 10;
+    let corner = &mut matrix[0][1];
+    *corner = 0;
+    assert_eq!(&matrix[1], &[13, 4]);
+    assert_eq!(matrix[1][0] + matrix[0][1], 13);
+
+    let mut grid = Grid {
+        rows: vec![Row { cells: vec![5; 3] }],
+    };
+    *&mut grid.rows[0].cells[2] = 7;
+    grid.rows[0].cells[1] = 6;
+    let cells = &mut grid.rows[0].cells;
+    cells.push(8);
+    assert_eq!(&grid.rows[0].cells[..], [5, 6, 7, 8]);
+
+    let mut planes = vec![BitVec::from_elem(false, 4); 2];
+    planes[1][3] = true;
+    {
+        let mut bit = &mut planes[0][2];
+        *bit = true;
+    }
+    assert!(planes[0][2] && planes[1][3] && !planes[1][0]);
+}
+
+/// Counts down, lending the count
+struct Countdown(u32);
+
+impl gat_std::iter::Iterator for Countdown {
+    type Item<'a> = &'a mut u32
+    where
+        Self: 'a;
+
+    fn next(&mut self) -> Option<Self::Item<'_>> {
+        if self.0 > 0 {
+            self.0 -= 1;
+            Some(&mut self.0)
+        } else {
+            None
+        }
+    }
+}
+
+#[gatify]
+fn labels() -> u32 {
+    let mut found = 0;
+    #[allow(unused_labels)]
+    'outer: for row in Countdown(10) {
+        let row = *row + 1;
+        let _iter = 0;
+        'inner: for col in 0..row {
+            if col == 2 {
+                continue 'outer;
+            } else if row < 3 {
+                break 'outer;
+            }
+            found += col + _iter;
+            if col == 0 {
+                continue 'inner;
+            }
+        }
+        drop(async {
+            for _ in Countdown(2) {
+                continue;
+            }
+        });
+    }
+    found += (0..3)
+        .map(|n| {
+            let mut out = 0;
+            for i in 0..5 {
+                if i > n {
+                    break;
+                }
+                out += i;
+            }
+            out
+        })
+        .sum::<u32>();
+    found
+}
+
+/// The same loops as `labels`, without `#[gatify]`
+fn labels_core() -> u32 {
+    use gat_std::iter::Iterator;
+
+    let mut found = 0;
+    let mut rows = Countdown(10);
+    #[allow(unused_labels)]
+    'outer: while let Some(row) = rows.next() {
+        let row = *row + 1;
+        let _iter = 0;
+        'inner: for col in 0..row {
+            if col == 2 {
+                continue 'outer;
+            } else if row < 3 {
+                break 'outer;
+            }
+            found += col + _iter;
+            if col == 0 {
+                continue 'inner;
+            }
+        }
+        drop(async {
+            let mut iter = Countdown(2);
+            while iter.next().is_some() {
+                continue;
+            }
+        });
+    }
+    found += (0..3)
+        .map(|n| {
+            let mut out = 0;
+            for i in 0..5 {
+                if i > n {
+                    break;
+                }
+                out += i;
+            }
+            out
+        })
+        .sum::<u32>();
+    found
+}
+
+#[test]
+fn for_loop_labels() {
+    assert_eq!(labels(), labels_core());
+    assert_eq!(labels(), 12);
+}
+
+mod facade {
+    pub use gat_std as gat;
+
+    macro_rules! assert {
+        ($val:expr) => {
+            ::core::stringify!($val)
+        };
+    }
+    pub(crate) use assert;
+}
+
+#[test]
+#[gatify]
+fn index_in_macros() {
+    let nums = vec![0, 1, 2];
+    let words = vec![String::from("a"), String::from("bc")];
+    let bits = BitVec::from_elem(true, 3);
+
+    assert_eq!(vec![nums[2]; nums[1]], [2]);
+    assert!(matches!(nums[1], 1..=2 if nums[2] == 2));
+    assert_eq!(format!("{}-{width}", words[1], width = nums[2]), "bc-2");
+    assert_eq!(format!("{}", bits[0]), "true");
+    core::assert!(nums[2] == 2);
+    // Unknown macros are left untouched, as are user macros sharing a name with a known one
+    assert_eq!(stringify!(nums[0]), "nums[0]");
+    assert_eq!(facade::assert!(nums[0]), "nums[0]");
+}
+
+#[test]
+#[gatify]
+fn known_macros() {
+    let pair = Pair(1, 2);
+    assert_eq!(dbg!(*pair), &1);
+
+    // A local macro shadows the `std` one of the same name, so its arguments are left as written
+    macro_rules! dbg {
+        ($val:expr) => {
+            ::core::stringify!($val)
+        };
+    }
+    assert_eq!(dbg!(*pair), "*pair");
+}
+
+#[gatify(crate = crate::facade::gat, skip(deref))]
+fn facade(bits: &mut BitVec, mut nums: Vec<u32>) -> u32 {
+    bits[0] = true;
+    let num = &mut nums[0];
+    // Only indexing is rewritten, so this is the core dereference
+    *num += 1;
+    nums.into_iter().sum()
+}
+
+#[gatify(index)]
+fn skipped(mut nums: Vec<u32>) -> u32 {
+    nums[0] = 1;
+    #[gatify::skip]
+    let first = &nums[0];
+    #[gatify::skip]
+    fn inner(nums: &[u32]) -> &u32 {
+        &nums[1]
+    }
+    *first + *inner(&nums) + #[gatify::skip] nums[2]
+}
+
+#[test]
+#[gatify]
+fn options() {
+    let mut bits = BitVec::from_elem(false, 2);
+    assert_eq!(facade(&mut bits, vec![1, 2]), 4);
+    assert!(bits[0]);
+    assert_eq!(skipped(vec![0, 2, 3]), 6);
+}
+
+struct Flags(BitVec);
+
+#[gatify]
+mod module {
+    use super::*;
+
+    pub fn count(flags: &Flags) -> usize {
+        let mut out = 0;
+        for i in 0..flags.0.len() {
+            if flags.0[i] {
+                out += 1;
+            }
+        }
+        out
+    }
+}
+
+#[gatify]
+impl Flags {
+    const LEN: usize = [4, 8][0];
+
+    fn set(&mut self, idx: usize) {
+        self.0[idx] = true;
+    }
+
+    const fn len() -> usize {
+        [Self::LEN][0]
+    }
+}
+
+impl Flags {
+    #[gatify]
+    fn get(&self, idx: usize) -> bool {
+        self.0[idx]
+    }
+
+    // An inner `#[gatify]` is expanded with its own arguments
+    #[gatify(for_loops)]
+    fn sum(&self) -> u32 {
+        let mut out = 0;
+        for val in Countdown(4) {
+            out += *val;
+        }
+        out
+    }
+}
+
+trait Values {
+    fn values(&self) -> &[u32];
+
+    #[gatify]
+    fn head(&self) -> u32 {
+        self.values()[0]
+    }
+}
+
+impl Values for Vec<u32> {
+    fn values(&self) -> &[u32] {
+        self
+    }
+}
+
+#[gatify]
+const FIRST: fn(&Flags) -> bool = |flags| flags.0[0];
+
+#[gatify]
+enum Size {
+    Small = [1, 2][0],
+}
+
+gat_std::gatify_block! {
+    fn total() -> u32 {
+        let mut out = 0;
+        for val in Countdown(3) {
+            out += *val;
+        }
+        out
+    }
+}
+
+#[test]
+fn items() {
+    let mut flags = Flags(BitVec::from_elem(false, Flags::len()));
+    flags.set(1);
+    flags.set(2);
+    assert!(flags.get(1) && !FIRST(&flags));
+    assert_eq!(module::count(&flags), 2);
+    assert_eq!(vec![3, 4].head(), 3);
+    assert_eq!(flags.sum(), 6);
+    assert_eq!(total(), 3);
+    assert_eq!(Size::Small as i32, 1);
+    assert_eq!(Flags::LEN, 4);
+
+    // Gatified regions inside non-gatified code
+    let last = gat_std::gatify_block! { flags.0[3] };
+    gat_std::gatify_block! {
+        #![gatify(index)]
+        let second = flags.0[1];
+        let check = |idx: usize| flags.0[idx];
+    }
+    let both = gat_std::gatify_block! {{
+        let third = flags.0[2];
+        second && third
+    }};
+    assert!(!last && second && both && check(2));
+}
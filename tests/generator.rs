@@ -0,0 +1,68 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use gat_std::iter::Iterator;
+use gat_std::lending_iter;
+
+type Log = Rc<RefCell<Vec<&'static str>>>;
+
+/// Records its name when dropped
+struct Noisy(&'static str, Log);
+
+impl Drop for Noisy {
+    fn drop(&mut self) {
+        self.1.borrow_mut().push(self.0);
+    }
+}
+
+fn noisy(log: &Log) -> impl for<'a> Iterator<Item<'a> = &'a str> {
+    lending_iter! {
+        move(log: Log = log.clone()) -> &str {
+            let outer: Noisy = Noisy("outer", log.clone());
+            {
+                let first: Noisy = Noisy("first", log.clone());
+                let second: Noisy = Noisy("second", log.clone());
+                yield first.0;
+                yield second.0;
+            }
+            yield outer.0;
+            let mut n: u32 = 0;
+            loop {
+                let each: Noisy = Noisy("each", log.clone());
+                n += 1;
+                if n == 2 {
+                    break;
+                }
+                yield each.0;
+            }
+            yield "end";
+        }
+    }
+}
+
+#[test]
+fn locals_drop_at_end_of_scope() {
+    let log = Log::default();
+    let mut iter = noisy(&log);
+
+    assert_eq!(iter.next(), Some("first"));
+    assert_eq!(iter.next(), Some("second"));
+    assert!(log.borrow().is_empty());
+    assert_eq!(iter.next(), Some("outer"));
+    assert_eq!(*log.borrow(), ["second", "first"]);
+    assert_eq!(iter.next(), Some("each"));
+    assert_eq!(iter.next(), Some("end"));
+    assert_eq!(*log.borrow(), ["second", "first", "each", "each"]);
+    assert_eq!(iter.next(), None);
+    assert_eq!(*log.borrow(), ["second", "first", "each", "each", "outer"]);
+}
+
+#[test]
+fn locals_drop_with_iterator() {
+    let log = Log::default();
+    let mut iter = noisy(&log);
+
+    assert_eq!(iter.next(), Some("first"));
+    drop(iter);
+    assert_eq!(*log.borrow(), ["second", "first", "outer"]);
+}
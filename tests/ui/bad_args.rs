@@ -0,0 +1,7 @@
+use gat_std::gatify;
+
+#[gatify(loops, skip(index, derefs))]
+fn main() {}
+
+#[gatify(crate = ::gat_std, crate = ::gat_std)]
+fn duplicate() {}
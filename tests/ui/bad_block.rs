@@ -0,0 +1,14 @@
+use gat_std::gatify_block;
+
+gatify_block! {
+    #![allow(unused)]
+    fn first() {}
+}
+
+gatify_block! {
+    #![gatify(index)]
+    #![gatify(deref)]
+    fn second() {}
+}
+
+fn main() {}
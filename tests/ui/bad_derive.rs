@@ -0,0 +1,23 @@
+#[derive(gat_std::Iterator)]
+enum Either {
+    Left,
+}
+
+#[derive(gat_std::Iterator)]
+struct Unmarked {
+    first: u32,
+    second: u32,
+}
+
+#[derive(gat_std::Iterator)]
+#[gat_std(forward)]
+struct Misplaced {
+    #[gat_std(forward)]
+    first: u32,
+    #[gat_std(forward, crate = ::gat_std)]
+    second: u32,
+    #[gat_std(reverse)]
+    third: u32,
+}
+
+fn main() {}
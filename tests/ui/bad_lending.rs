@@ -0,0 +1,46 @@
+use gat_std::lending_iterator;
+
+struct Counter(u32);
+
+#[lending_iterator]
+impl Counter {
+    fn next(&self) -> Option<&u32> {
+        Some(&self.0)
+    }
+
+    fn next_back(&mut self) -> &mut u32 {
+        &mut self.0
+    }
+}
+
+struct Empty;
+
+#[lending_iterator]
+impl Empty {
+    fn len(&self) -> usize {
+        0
+    }
+}
+
+#[lending_iterator]
+impl Clone for Empty {
+    fn clone(&self) -> Self {
+        Empty
+    }
+}
+
+impl Counter {
+    #[lending_iterator]
+    fn get(&mut self) -> Option<&mut u32> {
+        Some(&mut self.0)
+    }
+}
+
+impl Empty {
+    #[lending_iterator]
+    fn next(&mut self) -> Option<&mut Empty> {
+        Some(self)
+    }
+}
+
+fn main() {}
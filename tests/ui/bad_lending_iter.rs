@@ -0,0 +1,35 @@
+use gat_std::lending_iter;
+
+fn main() {
+    let _ = lending_iter! {
+        #![lending_iter(krate = gat_std)]
+        move() -> u32 {}
+    };
+
+    let _ = lending_iter! {
+        move(a: u32 = 1, a: u32 = 2) -> &u32 {
+            let b = 3;
+            for _ in 0..a {
+                yield &a;
+            }
+            match b {
+                _ => yield &a,
+            }
+            yield &a;
+        }
+    };
+
+    let _ = lending_iter! {
+        move(n: u32 = 0) -> u32 {
+            loop {
+                if n > 3 {
+                    break n;
+                }
+                let _m: u32 = (yield n);
+                drop(|| yield n);
+                yield n;
+            }
+            return n;
+        }
+    };
+}
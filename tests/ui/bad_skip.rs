@@ -0,0 +1,15 @@
+use gat_std::gatify;
+
+#[gatify]
+fn main() {
+    struct Point {
+        #[gatify::skip]
+        x: i32,
+    }
+
+    let p = Point { x: 0 };
+    #[gatify::skip(index)]
+    let _ = p.x;
+    #[gatify::skip = "deref"]
+    let _ = p.x;
+}
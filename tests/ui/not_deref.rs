@@ -0,0 +1,9 @@
+use gat_std::gatify;
+
+struct Empty;
+
+#[gatify]
+fn main() {
+    let val = Empty;
+    let _ = &*val;
+}
@@ -0,0 +1,8 @@
+use gat_std::gatify;
+
+struct Empty;
+
+#[gatify]
+fn main() {
+    for _ in Empty {}
+}